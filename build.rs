@@ -1,3 +1,67 @@
+use apache_arrow::array::{Array, LargeStringArray, StringArray};
+use bytes::Bytes;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::{env, fs, io::Read, path::Path};
+
 fn main() -> anyhow::Result<()> {
-    gmt_fem_code_builder::generate_fem(env!("CARGO_PKG_NAME"))
+    gmt_fem_code_builder::generate_fem(env!("CARGO_PKG_NAME"))?;
+    generate_io_names()
+}
+
+/// Reads the unique and sorted group names of an inputs or outputs table
+fn read_group_names(contents: Vec<u8>) -> anyhow::Result<Vec<String>> {
+    let parquet_reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(contents))?
+        .with_batch_size(2048)
+        .build()?;
+    let mut names = vec![];
+    for table in parquet_reader {
+        let table = table?;
+        let column = table.column(table.schema().index_of("group")?);
+        if let Some(groups) = column.as_any().downcast_ref::<StringArray>() {
+            names.extend(groups.iter().flatten().map(|x| x.to_string()));
+        } else if let Some(groups) = column.as_any().downcast_ref::<LargeStringArray>() {
+            names.extend(groups.iter().flatten().map(|x| x.to_string()));
+        } else {
+            anyhow::bail!("unexpected data type for the group column")
+        }
+    }
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// Writes the FEM inputs and outputs names into `fem_io_names.rs`
+///
+/// The names are empty if there is no FEM model in `FEM_REPO`
+fn generate_io_names() -> anyhow::Result<()> {
+    println!("cargo:rerun-if-env-changed=FEM_REPO");
+    let (inputs, outputs) = match env::var("FEM_REPO")
+        .map(|repo| Path::new(&repo).join("modal_state_space_model_2ndOrder.zip"))
+    {
+        Ok(path) if path.is_file() => {
+            println!("cargo:rerun-if-changed={}", path.display());
+            let mut zip_file = zip::ZipArchive::new(fs::File::open(path)?)?;
+            let mut contents = vec![];
+            zip_file
+                .by_name("rust/modal_state_space_model_2ndOrder_in.parquet")?
+                .read_to_end(&mut contents)?;
+            let inputs = read_group_names(contents)?;
+            let mut contents = vec![];
+            zip_file
+                .by_name("rust/modal_state_space_model_2ndOrder_out.parquet")?
+                .read_to_end(&mut contents)?;
+            let outputs = read_group_names(contents)?;
+            (inputs, outputs)
+        }
+        _ => (vec![], vec![]),
+    };
+    let out_dir = env::var("OUT_DIR")?;
+    fs::write(
+        Path::new(&out_dir).join("fem_io_names.rs"),
+        format!(
+            "/// FEM inputs names\npub const FEM_INPUT_NAMES: &[&str] = &{:?};\n/// FEM outputs names\npub const FEM_OUTPUT_NAMES: &[&str] = &{:?};\n",
+            inputs, outputs
+        ),
+    )?;
+    Ok(())
 }
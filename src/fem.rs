@@ -1,6 +1,6 @@
 use apache_arrow::{
-    array::{Float64Array, LargeStringArray, StringArray},
-    datatypes::SchemaRef,
+    array::{Float64Array, GenericStringArray, OffsetSizeTrait},
+    datatypes::{DataType, SchemaRef},
     record_batch::{RecordBatch, RecordBatchReader},
 };
 use bytes::Bytes;
@@ -12,12 +12,16 @@ use std::{
     env, fmt,
     fs::File,
     io::{BufReader, Read, Write},
+    mem,
+    ops::Range,
     path::Path,
 };
 use zip::{read::ZipFile, result::ZipError, ZipArchive};
 
 pub mod fem_io;
 pub mod io;
+mod npy;
+pub mod units;
 use io::{IOData, Properties, IO};
 
 #[derive(Debug, thiserror::Error)]
@@ -42,10 +46,38 @@ pub enum FemError {
     ReadTableColumn(String),
     #[error("failed to find {0} in zip archive {1}")]
     ZipNotFound(String, String),
+    #[error("matrix rows # ({0}) do not match outputs # ({1})")]
+    OutputsMismatch(usize, usize),
+    #[cfg(feature = "serde_json")]
+    #[error("failed to write JSON file")]
+    Json(#[from] serde_json::Error),
+    #[error("the number of damping coefficients ({0}) does not match the number of modes ({1})")]
+    DampingMismatch(usize, usize),
+    #[error("{0} matrix size ({1}) does not match the FEM dimensions [{2}x{3}]")]
+    MalformedMatrix(String, usize, usize, usize),
+    #[error("unknown FEM input or output {0}{}", closest_matches(.1))]
+    UnknownIO(String, Vec<String>),
+    #[error("invalid regular expression")]
+    Regex(#[from] regex::Error),
+    #[error("FEM input or output {0} is not in the model")]
+    MissingIO(String),
+    #[error("FEM input or output {0} is already in the model")]
+    DuplicateIO(String),
+    #[error("{0} has no component data")]
+    MissingComponent(String),
 }
 
 pub type Result<T> = std::result::Result<T, FemError>;
 
+/// Formats the closest matches of an unknown FEM input or output name, if any
+fn closest_matches(names: &[String]) -> String {
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(", closest matches: {}", names.join(", "))
+    }
+}
+
 /* mpl fmt::Display for FEMError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -106,97 +138,173 @@ fn read_table(contents: Vec<u8>) -> Result<Vec<(String, Vec<IO>)>> {
         .with_batch_size(2048)
         .build()?;
     let schema = parquet_reader.schema();
+    let large_utf8 = match schema
+        .field_with_name("group")
+        .map_err(|_| FemError::ReadTableColumn("group".to_string()))?
+        .data_type()
+    {
+        DataType::Utf8 => false,
+        DataType::LargeUtf8 => true,
+        _ => return Err(FemError::ReadTableColumn("group".to_string())),
+    };
     let mut io_map: HashMap<String, Vec<IO>> = HashMap::new();
     for maybe_table in parquet_reader {
         let Ok(table) = maybe_table else {
             panic!("Not a table!");
         };
-        read::<StringArray>(&schema, &table, "csLabel")?
-            .iter()
-            .zip(read::<Float64Array>(&schema, &table, "index")?.iter())
-            .zip(read::<Float64Array>(&schema, &table, "X")?.iter())
-            .zip(read::<Float64Array>(&schema, &table, "Y")?.iter())
-            .zip(read::<Float64Array>(&schema, &table, "Z")?.iter())
-            .zip(read::<StringArray>(&schema, &table, "description")?.iter())
-            .zip(read::<StringArray>(&schema, &table, "group")?.iter())
-            .filter_map(|data| {
-                if let ((((((Some(g), Some(f)), Some(e)), Some(d)), Some(c)), Some(b)), Some(a)) =
-                    data
-                {
-                    Some((g, f, e, d, c, b, a))
-                } else {
-                    None
-                }
-            })
-            .for_each(|(cs_label, index, x, y, z, description, group)| {
-                let value = IO::On(IOData {
-                    indices: vec![index as u32],
-                    descriptions: description.to_string(),
-                    properties: Properties {
-                        cs_label: Some(cs_label.to_string()),
-                        location: Some(vec![x, y, z]),
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                });
-                io_map
-                    .entry(group.to_string())
-                    .or_insert(vec![])
-                    .push(value)
-            });
+        if large_utf8 {
+            read_batch::<i64>(&schema, &table, &mut io_map)?;
+        } else {
+            read_batch::<i32>(&schema, &table, &mut io_map)?;
+        }
     }
     let mut sorted_map: Vec<_> = io_map.into_iter().collect();
     sorted_map.sort_by_key(|a| a.0.to_string());
     Ok(sorted_map)
 }
 
-fn read_table2(contents: Vec<u8>) -> Result<Vec<(String, Vec<IO>)>> {
-    let parquet_reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(contents))?
-        .with_batch_size(2048)
-        .build()?;
-    let schema = parquet_reader.schema();
-    let mut io_map: HashMap<String, Vec<IO>> = HashMap::new();
-    for maybe_table in parquet_reader {
-        let Ok(table) = maybe_table else {
-            panic!("Not a table!");
-        };
-        read::<LargeStringArray>(&schema, &table, "csLabel")?
-            .iter()
-            .zip(read::<Float64Array>(&schema, &table, "index")?.iter())
-            .zip(read::<Float64Array>(&schema, &table, "X")?.iter())
-            .zip(read::<Float64Array>(&schema, &table, "Y")?.iter())
-            .zip(read::<Float64Array>(&schema, &table, "Z")?.iter())
-            .zip(read::<LargeStringArray>(&schema, &table, "description")?.iter())
-            .zip(read::<LargeStringArray>(&schema, &table, "group")?.iter())
-            .filter_map(|data| {
-                if let ((((((Some(g), Some(f)), Some(e)), Some(d)), Some(c)), Some(b)), Some(a)) =
-                    data
-                {
-                    Some((g, f, e, d, c, b, a))
-                } else {
-                    None
-                }
-            })
-            .for_each(|(cs_label, index, x, y, z, description, group)| {
-                let value = IO::On(IOData {
-                    indices: vec![index as u32],
-                    descriptions: description.to_string(),
-                    properties: Properties {
-                        cs_label: Some(cs_label.to_string()),
-                        location: Some(vec![x, y, z]),
-                        ..Default::default()
-                    },
+fn read_batch<O: OffsetSizeTrait>(
+    schema: &SchemaRef,
+    table: &RecordBatch,
+    io_map: &mut HashMap<String, Vec<IO>>,
+) -> Result<()> {
+    read::<GenericStringArray<O>>(schema, table, "csLabel")?
+        .iter()
+        .zip(read::<Float64Array>(schema, table, "index")?.iter())
+        .zip(read::<Float64Array>(schema, table, "X")?.iter())
+        .zip(read::<Float64Array>(schema, table, "Y")?.iter())
+        .zip(read::<Float64Array>(schema, table, "Z")?.iter())
+        .zip(read::<GenericStringArray<O>>(schema, table, "description")?.iter())
+        .zip(read::<GenericStringArray<O>>(schema, table, "group")?.iter())
+        .filter_map(|data| {
+            if let ((((((Some(g), Some(f)), Some(e)), Some(d)), Some(c)), Some(b)), Some(a)) = data
+            {
+                Some((g, f, e, d, c, b, a))
+            } else {
+                None
+            }
+        })
+        .for_each(|(cs_label, index, x, y, z, description, group)| {
+            let value = IO::On(IOData {
+                indices: vec![index as u32],
+                descriptions: description.to_string(),
+                properties: Properties {
+                    cs_label: Some(cs_label.to_string()),
+                    location: Some(vec![x, y, z]),
                     ..Default::default()
-                });
-                io_map
-                    .entry(group.to_string())
-                    .or_insert(vec![])
-                    .push(value)
+                },
+                ..Default::default()
             });
+            io_map
+                .entry(group.to_string())
+                .or_insert(vec![])
+                .push(value)
+        });
+    Ok(())
+}
+
+/// Collects the node ids of the turned-on entries of an input or output
+fn node_ids<'a>(io: impl Iterator<Item = &'a IO>) -> Option<Vec<u32>> {
+    io.filter_map(|x| x.get_by(|data| Some(data.properties.node_id.clone())))
+        .collect::<Option<Vec<Vec<u32>>>>()
+        .map(|ids| ids.concat())
+}
+/// Key matching an input or output across models: the node id (or the index if there is no node id),
+/// the component and the rank of the entry among the entries with the same node id and component
+type IoKey = (Vec<u32>, Option<Vec<i32>>, usize);
+fn io_keys<'a>(data: impl Iterator<Item = &'a IOData>) -> Vec<IoKey> {
+    let mut ranks: HashMap<(Vec<u32>, Option<Vec<i32>>), usize> = HashMap::new();
+    data.map(|data| {
+        let node = data
+            .properties
+            .node_id
+            .clone()
+            .unwrap_or_else(|| data.indices.clone());
+        let component = data.properties.component.clone();
+        let rank = ranks.entry((node.clone(), component.clone())).or_default();
+        *rank += 1;
+        (node, component, *rank - 1)
+    })
+    .collect()
+}
+/// Returns the state (on or off) of the inputs or outputs
+fn switches<'a>(io: impl Iterator<Item = &'a IO>) -> HashMap<IoKey, bool> {
+    let io: Vec<_> = io.collect();
+    io_keys(io.iter().map(|&io| {
+        let (IO::On(data) | IO::Off(data)) = io;
+        data
+    }))
+    .into_iter()
+    .zip(io.iter().map(|io| io.is_on()))
+    .collect()
+}
+/// Turns on or off the inputs or outputs according to the state of their match in `switches`,
+/// inputs or outputs without a match are turned off
+fn copy_switches<'a>(io: impl Iterator<Item = &'a mut IO>, switches: &HashMap<IoKey, bool>) {
+    let io: Vec<_> = io.collect();
+    let keys = io_keys(io.iter().map(|io| {
+        let (IO::On(data) | IO::Off(data)) = &**io;
+        data
+    }));
+    io.into_iter().zip(keys).for_each(|(io, key)| {
+        *io = if switches.get(&key).copied().unwrap_or_default() {
+            io.clone().switch_on()
+        } else {
+            io.clone().switch_off()
+        };
+    })
+}
+/// Copies the selection of the groups of inputs or outputs `others` to the matching groups of `groups`,
+/// the groups without a match are removed
+fn clone_groups_selection<T>(
+    groups: &mut [Option<T>],
+    others: &[Option<T>],
+    switches: impl Fn(&T) -> HashMap<IoKey, bool>,
+    copy_switches: impl Fn(&mut T, &HashMap<IoKey, bool>),
+) {
+    groups.iter_mut().for_each(|group| {
+        let Some(g) = group.as_mut() else {
+            return;
+        };
+        match others
+            .iter()
+            .flatten()
+            .find(|other| mem::discriminant(*other) == mem::discriminant(&*g))
+        {
+            Some(other) => copy_switches(g, &switches(other)),
+            None => *group = None,
+        }
+    })
+}
+/// Checks if an input or output corresponds to one of the degrees of freedom `axes` (0-based)
+///
+/// The degree of freedom is given either by the non-zero entries of `components`
+/// or by the 1-based `component` numbers, if neither is present `None` is returned
+fn is_component(data: &IOData, axes: &[usize]) -> Option<bool> {
+    match (&data.properties.components, &data.properties.component) {
+        (Some(components), _) => Some(
+            axes.iter()
+                .any(|&a| components.get(a).is_some_and(|&x| x != 0f64)),
+        ),
+        (None, Some(component)) => Some(
+            component
+                .iter()
+                .any(|&c| c > 0 && axes.contains(&(c as usize - 1))),
+        ),
+        (None, None) => None,
     }
-    let mut sorted_map: Vec<_> = io_map.into_iter().collect();
-    sorted_map.sort_by_key(|a| a.0.to_string());
-    Ok(sorted_map)
+}
+/// Checks which of the inputs or outputs correspond to one of the degrees of freedom `axes` (0-based)
+///
+/// The turned-off inputs or outputs are not checked and an error is returned
+/// if one of the turned-on inputs or outputs has no component data
+fn components_mask<'a>(io: impl Iterator<Item = &'a IO>, axes: &[usize]) -> Result<Vec<bool>> {
+    io.map(|io| match io {
+        IO::On(data) => is_component(data, axes)
+            .ok_or_else(|| FemError::MissingComponent(data.descriptions.clone())),
+        IO::Off(_) => Ok(false),
+    })
+    .collect()
 }
 
 fn read_contents(mut zip_file: ZipFile) -> Result<Vec<u8>> {
@@ -205,6 +313,15 @@ fn read_contents(mut zip_file: ZipFile) -> Result<Vec<u8>> {
     Ok(contents)
 }
 
+/// Loads a Matlab file from a zip archive entry and reads its contents with `f`
+fn read_mat_file<T>(zip_file: ZipFile, f: impl FnOnce(&MatFile) -> Result<T>) -> Result<T> {
+    let contents = read_contents(zip_file)?;
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(contents.as_slice())?;
+    file.flush()?;
+    f(&MatFile::load(file.path())?)
+}
+
 fn read_mat(zip_file: &mut ZipArchive<BufReader<File>>, name: &str) -> Result<Vec<f64>> {
     let mat_file_name = format!("rust/{}.mat", name);
     let mut i = 1;
@@ -216,11 +333,7 @@ fn read_mat(zip_file: &mut ZipArchive<BufReader<File>>, name: &str) -> Result<Ve
             i,
             mat_file_name
         );
-        let contents = read_contents(mat_file)?;
-        let mut file = tempfile::NamedTempFile::new()?;
-        file.write_all(contents.as_slice())?;
-        file.flush()?;
-        let mut data: Vec<f64> = MatFile::load(file.path())?.var(format!("slice"))?;
+        let mut data: Vec<f64> = read_mat_file(mat_file, |mat_file| Ok(mat_file.var("slice")?))?;
         maybe_data.get_or_insert(vec![]).append(&mut data);
         i += 1;
     }
@@ -229,20 +342,39 @@ fn read_mat(zip_file: &mut ZipArchive<BufReader<File>>, name: &str) -> Result<Ve
     } else {
         let mat_file = zip_file.by_name(&mat_file_name)?;
         log::info!(r#"loading {} from "{}""#, name, mat_file_name);
-        let contents = read_contents(mat_file)?;
-        let mut file = tempfile::NamedTempFile::new()?;
-        file.write_all(contents.as_slice())?;
-        file.flush()?;
-        let data = MatFile::load(file.path())?.var(name)?;
-        data
+        read_mat_file(mat_file, |mat_file| Ok(mat_file.var(name)?))?
     };
     Ok(data)
 }
 
+/// Reads a matrix from a zip archive, returning `None` if the matrix is not in the archive
+fn read_optional_mat(
+    zip_file: &mut ZipArchive<BufReader<File>>,
+    name: &str,
+) -> Result<Option<Vec<f64>>> {
+    match read_mat(zip_file, name) {
+        Ok(data) => Ok(Some(data)),
+        Err(FemError::ZipReader(ZipError::FileNotFound)) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Returns the name of a group of inputs or outputs
+///
+/// The name is the label the group `Display` implementation writes before the entry count
+fn group_name(group: &impl fmt::Display) -> String {
+    let label = group.to_string();
+    label
+        .split_once(':')
+        .map_or(label.as_str(), |(name, _)| name)
+        .trim()
+        .to_string()
+}
+
 fn read_inputs(zip_file: &mut ZipArchive<BufReader<File>>) -> Result<Vec<Option<fem_io::Inputs>>> {
     log::info!(r#"reading inputs table from "modal_state_space_model_2ndOrder_in.parquet""#);
     read_contents(zip_file.by_name("rust/modal_state_space_model_2ndOrder_in.parquet")?)
-        .and_then(|contents| read_table(contents.clone()).or_else(|_| read_table2(contents)))?
+        .and_then(read_table)?
         .into_iter()
         .map(|item| Some(fem_io::Inputs::try_from(item)).transpose())
         .collect()
@@ -253,12 +385,72 @@ fn read_outputs(
 ) -> Result<Vec<Option<fem_io::Outputs>>> {
     log::info!(r#"reading outputs table from "modal_state_space_model_2ndOrder_out.parquet""#);
     read_contents(zip_file.by_name("rust/modal_state_space_model_2ndOrder_out.parquet")?)
-        .and_then(|contents| read_table(contents.clone()).or_else(|_| read_table2(contents)))?
+        .and_then(read_table)?
         .into_iter()
         .map(|item| Some(fem_io::Outputs::try_from(item)).transpose())
         .collect()
 }
 
+/// Returns a 64 bits hash of the contents of `reader`
+///
+/// The contents are hashed by chunks without being held in memory.
+/// The hash is only meant to be stable within a process and is not suitable for long term storage
+#[cfg(feature = "cache")]
+fn content_hash<R: Read>(mut reader: R) -> Result<u64> {
+    use std::hash::Hasher;
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => break,
+            n => hasher.write(&buffer[..n]),
+        }
+    }
+    Ok(hasher.finish())
+}
+#[cfg(feature = "cache")]
+fn fem_cache() -> &'static std::sync::Mutex<HashMap<u64, FEM>> {
+    static FEM_CACHE: std::sync::OnceLock<std::sync::Mutex<HashMap<u64, FEM>>> =
+        std::sync::OnceLock::new();
+    FEM_CACHE.get_or_init(Default::default)
+}
+
+/// FEM zip archive loading options
+///
+/// The scales convert the archive units into the units of the loaded model
+/// (`F_model = force_scale F_archive` and `x_model = length_scale x_archive`), see [FEM::scale_units]
+#[derive(Debug, Clone, Copy)]
+pub struct FemLoadOpts {
+    /// `inputs2ModalF` and `modalDisp2Outputs` are stored row wise (default: `true`)
+    pub row_major: bool,
+    /// length unit scaling factor (default: `1.0`)
+    pub length_scale: f64,
+    /// force unit scaling factor (default: `1.0`)
+    pub force_scale: f64,
+}
+impl Default for FemLoadOpts {
+    fn default() -> Self {
+        Self {
+            row_major: true,
+            length_scale: 1f64,
+            force_scale: 1f64,
+        }
+    }
+}
+
+/// GMT Finite Element Model metadata
+///
+/// The inputs and outputs properties and the eigen frequencies of a FEM, without the modal matrices
+#[derive(Debug, Clone, Default)]
+pub struct FemMetadata {
+    /// inputs properties
+    pub inputs: Vec<Option<fem_io::Inputs>>,
+    /// outputs properties
+    pub outputs: Vec<Option<fem_io::Outputs>>,
+    /// mode shapes eigen frequencies `[Hz]`
+    pub eigen_frequencies: Vec<f64>,
+}
+
 /// GMT Finite Element Model
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Default)]
@@ -284,6 +476,9 @@ pub struct FEM {
     pub proportional_damping_vec: Vec<f64>,
     #[cfg_attr(feature = "serde", serde(rename = "gainMatrix"))]
     pub static_gain: Option<Vec<f64>>,
+    /// static gains `[n_outputs,n_inputs]` (row wise) keyed by name
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub named_static_gains: HashMap<String, Vec<f64>>,
     /// number of inputs and outputs before any model reduction
     #[cfg_attr(feature = "serde", serde(skip))]
     pub n_io: (usize, usize),
@@ -301,10 +496,72 @@ impl FEM {
         let mut fem: FEM = serde_pickle::from_value(v)?;
         fem.n_io = (fem.n_inputs(), fem.n_outputs());
         fem.model = path.as_ref().to_str().unwrap().to_string();
+        fem.check()?;
         Ok(fem)
     }
     /// Loads a FEM model, saved in a second order form, from a zip archive file
     pub fn from_zip_archive<P: AsRef<Path>>(path: P) -> Result<FEM> {
+        Self::from_zip_archive_with(path, FemLoadOpts::default())
+    }
+    /// Loads a FEM model, saved in a second order form, from a zip archive split into several files
+    ///
+    /// The parts are concatenated in the order of `paths`, e.g.
+    /// `modal_state_space_model_2ndOrder.zip.001`, `modal_state_space_model_2ndOrder.zip.002`, ...,
+    /// and the central directory of the reconstructed archive is checked before loading the model
+    pub fn from_zip_parts(paths: &[&Path]) -> Result<FEM> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        for path in paths {
+            log::info!("Appending {path:?} to the FEM zip archive");
+            std::io::copy(&mut File::open(path)?, &mut file)?;
+        }
+        file.flush()?;
+        zip::ZipArchive::new(BufReader::new(File::open(file.path())?))?;
+        let mut fem = Self::from_zip_archive(file.path())?;
+        if let Some(path) = paths.first() {
+            fem.model = path.to_str().unwrap().to_string();
+        }
+        Ok(fem)
+    }
+    /// Loads a FEM model, saved in a second order form, from a zip archive file with the given loading options
+    ///
+    /// The static gains (`static_gain` and `static_gain_<name>`) are optional and are skipped if they are not in the archive,
+    /// but a static gain that cannot be read or that does not match the FEM dimensions fails the loading.
+    ///
+    /// With the `cache` feature, models are memoized in-process keyed by the content hash
+    /// of the archive and of the loading options, and a clone of the model is returned on a cache hit.
+    /// The archive is still read once per call to compute its hash, and the cached models are held in memory
+    /// for the whole process unless [FEM::clear_cache] is called
+    pub fn from_zip_archive_with<P: AsRef<Path>>(path: P, opts: FemLoadOpts) -> Result<FEM> {
+        #[cfg(feature = "cache")]
+        {
+            use std::hash::{Hash, Hasher};
+            let path = path.as_ref();
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            content_hash(File::open(path)?)?.hash(&mut hasher);
+            opts.row_major.hash(&mut hasher);
+            opts.length_scale.to_bits().hash(&mut hasher);
+            opts.force_scale.to_bits().hash(&mut hasher);
+            let key = hasher.finish();
+            if let Some(fem) = fem_cache().lock().unwrap().get(&key) {
+                log::info!("Loading FEM from {path:?} (cached)");
+                return Ok(FEM {
+                    model: path.to_str().unwrap().to_string(),
+                    ..fem.clone()
+                });
+            }
+            let fem = Self::load_zip_archive(path, opts)?;
+            fem_cache().lock().unwrap().insert(key, fem.clone());
+            Ok(fem)
+        }
+        #[cfg(not(feature = "cache"))]
+        Self::load_zip_archive(path, opts)
+    }
+    /// Removes all the models from the in-process cache of [FEM::from_zip_archive_with]
+    #[cfg(feature = "cache")]
+    pub fn clear_cache() {
+        fem_cache().lock().unwrap().clear();
+    }
+    fn load_zip_archive<P: AsRef<Path>>(path: P, opts: FemLoadOpts) -> Result<FEM> {
         let path = path.as_ref();
         log::info!("Loading FEM from {path:?}");
         let file = File::open(path)?;
@@ -328,38 +585,303 @@ impl FEM {
 
         let modal_disp_to_outputs: Vec<f64> = read_mat(&mut zip_file, "modalDisp2Outputs")?;
 
-        let static_gain = read_mat(&mut zip_file, "static_gain").ok();
+        let static_gain = read_optional_mat(&mut zip_file, "static_gain")?;
+
+        let mut gain_names: Vec<String> = zip_file
+            .file_names()
+            .filter_map(|file_name| file_name.strip_prefix("rust/static_gain_"))
+            .filter_map(|file_name| file_name.split_once(".mat"))
+            .map(|(name, _)| name.to_string())
+            .collect();
+        gain_names.sort();
+        gain_names.dedup();
+        let named_static_gains = gain_names
+            .into_iter()
+            .map(|name| {
+                read_mat(&mut zip_file, &format!("static_gain_{name}")).map(|gain| (name, gain))
+            })
+            .collect::<Result<HashMap<String, Vec<f64>>>>()?;
 
         log::info!(r#"loading FEM properties from "modal_state_space_model_2ndOrder_mat.mat""#);
-        let mat_file = zip_file.by_name("rust/modal_state_space_model_2ndOrder_mat.mat")?;
-        let contents = read_contents(mat_file)?;
-        let mut file = tempfile::NamedTempFile::new()?;
-        file.write_all(contents.as_slice())?;
-        file.flush()?;
-        let mat_file = MatFile::load(file.path())?;
+        let (model_description, eigen_frequencies, proportional_damping_vec): (
+            String,
+            Vec<f64>,
+            Vec<f64>,
+        ) = read_mat_file(
+            zip_file.by_name("rust/modal_state_space_model_2ndOrder_mat.mat")?,
+            |mat_file| {
+                // the model description is optional, older archives do not have it
+                let model_description = match mat_file.var("modelDescription") {
+                    Ok(model_description) => model_description,
+                    Err(MatioError::MatVarRead(_)) => String::new(),
+                    Err(e) => return Err(e.into()),
+                };
+                Ok((
+                    model_description,
+                    mat_file.var("eigenfrequencies")?,
+                    mat_file.var("proportionalDampingVec")?,
+                ))
+            },
+        )?;
 
-        Ok(FEM {
+        let mut fem = FEM {
             inputs,
             outputs,
-            // model_description: mat_file.var("modelDescription")?,
-            eigen_frequencies: mat_file.var("eigenfrequencies")?,
+            model_description,
+            eigen_frequencies,
             inputs_to_modal_forces,
             modal_disp_to_outputs,
-            proportional_damping_vec: mat_file.var("proportionalDampingVec")?,
+            proportional_damping_vec,
             static_gain,
+            named_static_gains,
             n_io,
             model: path.to_str().unwrap().to_string(),
             ..Default::default()
+        };
+        fem.check()?;
+        if !opts.row_major {
+            fem.transpose_modal_matrices();
+        }
+        if opts.length_scale != 1f64 || opts.force_scale != 1f64 {
+            fem.scale_units(opts.length_scale, opts.force_scale);
+        }
+        Ok(fem)
+    }
+    /// Converts the units of the model
+    ///
+    /// The scales convert the current units into the new ones, i.e. `F_new = force_scale F` and `x_new = length_scale x`:
+    ///  - the inputs and outputs nodes location and the modes to outputs matrix are multiplied by `length_scale`,
+    ///  - the inputs to modal forces matrix is divided by `force_scale`,
+    ///  - the static gains are multiplied by `length_scale / force_scale`.
+    ///
+    /// All the outputs are scaled by `length_scale`, including the rotations and the other non-length outputs,
+    /// and all the inputs are scaled by `force_scale`, including the moments
+    pub fn scale_units(&mut self, length_scale: f64, force_scale: f64) -> &mut Self {
+        log::info!("scaling lengths by {length_scale} and forces by {force_scale}");
+        let scale_location = |io: &mut IO| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            if let Some(location) = data.properties.location.as_mut() {
+                location.iter_mut().for_each(|x| *x *= length_scale);
+            }
+        };
+        self.inputs
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .for_each(|i| i.iter_mut().for_each(scale_location));
+        self.outputs
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .for_each(|o| o.iter_mut().for_each(scale_location));
+        self.inputs_to_modal_forces
+            .iter_mut()
+            .for_each(|x| *x /= force_scale);
+        self.modal_disp_to_outputs
+            .iter_mut()
+            .for_each(|x| *x *= length_scale);
+        self.static_gain
+            .iter_mut()
+            .chain(self.named_static_gains.values_mut())
+            .for_each(|gain| {
+                gain.iter_mut()
+                    .for_each(|x| *x *= length_scale / force_scale)
+            });
+        self
+    }
+    /// Creates a synthetic FEM model
+    ///
+    /// The model has `n_modes` modes, the first 3 being rigid body modes, and
+    /// the given inputs and outputs as `(name,number of inputs or outputs)`.
+    /// The inputs and outputs names must be valid FEM io names (see [fem_io::FEM_INPUT_NAMES] and [fem_io::FEM_OUTPUT_NAMES]).
+    /// The modal matrices and the nodes location are random and the static gain is computed from the modes
+    pub fn synthetic(
+        n_modes: usize,
+        inputs: &[(&str, usize)],
+        outputs: &[(&str, usize)],
+    ) -> Result<Self> {
+        // inputs or outputs with indices starting after `offset`
+        fn io(name: &str, offset: usize, n: usize) -> Vec<IO> {
+            (offset + 1..=offset + n)
+                .map(|index| {
+                    IO::On(IOData {
+                        indices: vec![index as u32],
+                        descriptions: format!("{name} #{index}"),
+                        properties: Properties {
+                            location: Some(na::Vector3::<f64>::new_random().as_slice().to_vec()),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                })
+                .collect()
+        }
+        let mut offset = 0;
+        let inputs = inputs
+            .iter()
+            .map(|&(name, n)| {
+                fem_io::Inputs::try_from(name)?;
+                let group = fem_io::Inputs::try_from((name.to_string(), io(name, offset, n)))?;
+                offset += n;
+                Ok(Some(group))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let n_inputs = offset;
+        let mut offset = 0;
+        let outputs = outputs
+            .iter()
+            .map(|&(name, n)| {
+                fem_io::Outputs::try_from(name)?;
+                let group = fem_io::Outputs::try_from((name.to_string(), io(name, offset, n)))?;
+                offset += n;
+                Ok(Some(group))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let n_outputs = offset;
+        let mut fem = FEM {
+            model_description: "synthetic FEM".to_string(),
+            inputs,
+            outputs,
+            eigen_frequencies: (0..n_modes).map(|k| k.saturating_sub(2) as f64).collect(),
+            inputs_to_modal_forces: na::DMatrix::<f64>::new_random(n_modes * n_inputs, 1)
+                .as_slice()
+                .to_vec(),
+            modal_disp_to_outputs: na::DMatrix::<f64>::new_random(n_outputs * n_modes, 1)
+                .as_slice()
+                .to_vec(),
+            proportional_damping_vec: vec![0.02; n_modes],
+            n_io: (n_inputs, n_outputs),
+            model: "synthetic".to_string(),
+            ..Default::default()
+        };
+        if n_modes > 3 {
+            fem.static_gain = Some(fem.static_gain().transpose().as_slice().to_vec());
+        }
+        Ok(fem)
+    }
+    /// Reads the inputs table from a standalone parquet file, e.g. `modal_state_space_model_2ndOrder_in.parquet`
+    pub fn inputs_from_parquet<P: AsRef<Path>>(path: P) -> Result<Vec<Option<fem_io::Inputs>>> {
+        log::info!("reading inputs table from {:?}", path.as_ref());
+        read_table(std::fs::read(path)?)?
+            .into_iter()
+            .map(|item| Some(fem_io::Inputs::try_from(item)).transpose())
+            .collect()
+    }
+    /// Reads the outputs table from a standalone parquet file, e.g. `modal_state_space_model_2ndOrder_out.parquet`
+    pub fn outputs_from_parquet<P: AsRef<Path>>(path: P) -> Result<Vec<Option<fem_io::Outputs>>> {
+        log::info!("reading outputs table from {:?}", path.as_ref());
+        read_table(std::fs::read(path)?)?
+            .into_iter()
+            .map(|item| Some(fem_io::Outputs::try_from(item)).transpose())
+            .collect()
+    }
+    /// Loads the inputs and outputs tables and the eigen frequencies of a FEM model from a zip archive file
+    ///
+    /// The modal matrices and the static gain are not read
+    pub fn metadata_from_zip<P: AsRef<Path>>(path: P) -> Result<FemMetadata> {
+        let path = path.as_ref();
+        log::info!("Loading FEM metadata from {path:?}");
+        let file = File::open(path)?;
+        let buffer = BufReader::new(file);
+        let mut zip_file = zip::ZipArchive::new(buffer)?;
+
+        let inputs = read_inputs(&mut zip_file)?;
+        let outputs = read_outputs(&mut zip_file)?;
+
+        log::info!(r#"loading FEM properties from "modal_state_space_model_2ndOrder_mat.mat""#);
+        let eigen_frequencies: Vec<f64> = read_mat_file(
+            zip_file.by_name("rust/modal_state_space_model_2ndOrder_mat.mat")?,
+            |mat_file| Ok(mat_file.var("eigenfrequencies")?),
+        )?;
+
+        Ok(FemMetadata {
+            inputs,
+            outputs,
+            eigen_frequencies,
         })
     }
     /// Loads a FEM model, saved in a second order form, from a zip archive file located in a directory given by the `FEM_REPO` environment variable
     ///
     /// The name of the zip file must be `"modal_state_space_model_2ndOrder.zip`
     pub fn from_env() -> Result<Self> {
+        Self::from_env_named("modal_state_space_model_2ndOrder.zip")
+        // .or_else(|_| Self::from_pickle(&path.join("modal_state_space_model_2ndOrder.73.pkl")))
+    }
+    /// Loads a FEM model, saved in a second order form, from the zip archive `name` located in a directory given by the `FEM_REPO` environment variable
+    pub fn from_env_named(name: &str) -> Result<Self> {
         let fem_repo = env::var("FEM_REPO")?;
         let path = Path::new(&fem_repo);
-        Self::from_zip_archive(path.join("modal_state_space_model_2ndOrder.zip"))
-        // .or_else(|_| Self::from_pickle(&path.join("modal_state_space_model_2ndOrder.73.pkl")))
+        Self::from_zip_archive(path.join(name))
+    }
+    /// Checks the consistency of the model dimensions
+    fn check(&self) -> Result<()> {
+        if self.proportional_damping_vec.len() != self.n_modes() {
+            return Err(FemError::DampingMismatch(
+                self.proportional_damping_vec.len(),
+                self.n_modes(),
+            ));
+        }
+        let n_modes = self.n_modes();
+        if self.inputs_to_modal_forces.len() != n_modes * self.n_io.0 {
+            return Err(FemError::MalformedMatrix(
+                "inputs2ModalF".to_string(),
+                self.inputs_to_modal_forces.len(),
+                n_modes,
+                self.n_io.0,
+            ));
+        }
+        if self.modal_disp_to_outputs.len() != self.n_io.1 * n_modes {
+            return Err(FemError::MalformedMatrix(
+                "modalDisp2Outputs".to_string(),
+                self.modal_disp_to_outputs.len(),
+                self.n_io.1,
+                n_modes,
+            ));
+        }
+        for (name, gain) in self
+            .static_gain
+            .iter()
+            .map(|gain| ("static_gain".to_string(), gain))
+            .chain(
+                self.named_static_gains
+                    .iter()
+                    .map(|(name, gain)| (format!("static_gain_{name}"), gain)),
+            )
+        {
+            if gain.len() != self.n_io.0 * self.n_io.1 {
+                return Err(FemError::MalformedMatrix(
+                    name,
+                    gain.len(),
+                    self.n_io.1,
+                    self.n_io.0,
+                ));
+            }
+        }
+        Ok(())
+    }
+    /// Transposes the inputs to modes and modes to outputs matrices
+    ///
+    /// The expected storage is row wise: `inputs_to_modal_forces` is `[n_modes,n_inputs]`
+    /// and `modal_disp_to_outputs` is `[n_outputs,n_modes]`.
+    /// For an archive exported column wise, this method converts both matrices to the row wise convention
+    pub fn transpose_modal_matrices(&mut self) -> &mut Self {
+        log::info!("transposing the modal matrices");
+        let n_modes = self.n_modes();
+        let n_inputs = self.inputs_to_modal_forces.len() / n_modes;
+        self.inputs_to_modal_forces =
+            na::DMatrix::from_column_slice(n_modes, n_inputs, &self.inputs_to_modal_forces)
+                .transpose()
+                .as_slice()
+                .to_vec();
+        let n_outputs = self.modal_disp_to_outputs.len() / n_modes;
+        self.modal_disp_to_outputs =
+            na::DMatrix::from_column_slice(n_outputs, n_modes, &self.modal_disp_to_outputs)
+                .transpose()
+                .as_slice()
+                .to_vec();
+        self
+    }
+    /// Gets the model description
+    pub fn description(&self) -> &str {
+        &self.model_description
     }
     /// Gets the number of modes
     pub fn n_modes(&self) -> usize {
@@ -372,6 +894,97 @@ impl FEM {
             .map(|x| 2.0 * std::f64::consts::PI * x)
             .collect()
     }
+    /// Gets the FEM eigen frequencies in Hz
+    pub fn eigen_frequencies_hz(&self) -> Vec<units::Hz> {
+        self.eigen_frequencies
+            .iter()
+            .map(|x| units::Hz(*x))
+            .collect()
+    }
+    /// Gets the FEM eigen frequencies in radians per second
+    pub fn eigen_frequencies_rad_per_sec(&self) -> Vec<units::RadPerSec> {
+        self.eigen_frequencies_hz()
+            .into_iter()
+            .map(units::RadPerSec::from)
+            .collect()
+    }
+    /// Sets the modes damping coefficients according to Rayleigh damping
+    ///
+    /// The damping coefficient of each mode with eigen frequency `ω` `[rd/s]` is `ζ(ω) = (α/ω + βω)/2`,
+    /// rigid body modes (`ω=0`) are left undamped
+    pub fn rayleigh_damping(&mut self, alpha: f64, beta: f64) -> &mut Self {
+        self.proportional_damping_vec = self
+            .eigen_frequencies_to_radians()
+            .into_iter()
+            .map(|w| {
+                if w > 0f64 {
+                    0.5 * (alpha / w + beta * w)
+                } else {
+                    0f64
+                }
+            })
+            .collect();
+        self
+    }
+    /// Iterates over the selected inputs yielding the group index, the group name and the number of turned-on inputs
+    pub fn inputs_iter(&self) -> impl Iterator<Item = (usize, String, usize)> + '_ {
+        self.inputs
+            .iter()
+            .enumerate()
+            .filter_map(|(k, x)| x.as_ref().map(|x| (k, group_name(x), x.len())))
+    }
+    /// Iterates over the selected outputs yielding the group index, the group name and the number of turned-on outputs
+    pub fn outputs_iter(&self) -> impl Iterator<Item = (usize, String, usize)> + '_ {
+        self.outputs
+            .iter()
+            .enumerate()
+            .filter_map(|(k, x)| x.as_ref().map(|x| (k, group_name(x), x.len())))
+    }
+    /// Scales all the outputs by a given factor
+    ///
+    /// The modes to outputs matrix and the static gains are multiplied by `scale`
+    pub fn apply_gain(&mut self, scale: f64) -> &mut Self {
+        log::info!("scaling the outputs by {scale}");
+        self.modal_disp_to_outputs
+            .iter_mut()
+            .for_each(|x| *x *= scale);
+        self.static_gain
+            .iter_mut()
+            .chain(self.named_static_gains.values_mut())
+            .for_each(|gain| gain.iter_mut().for_each(|x| *x *= scale));
+        self
+    }
+    /// Scales a given input by `factor`
+    ///
+    /// The columns of the inputs to modal forces matrix and of the static gains
+    /// corresponding to all the entries (turned on or off) of the input are multiplied by `factor`.
+    /// An error is returned if the input is not selected
+    pub fn scale_input(&mut self, id: usize, factor: f64) -> Result<&mut Self> {
+        let input = self
+            .inputs
+            .get(id)
+            .and_then(|x| x.as_ref())
+            .ok_or_else(|| FemError::MissingIO(format!("input #{id}")))?;
+        log::info!("scaling the input #{id} by {factor}");
+        let indices: Vec<usize> = input
+            .iter()
+            .flat_map(|x| {
+                let (IO::On(io) | IO::Off(io)) = x;
+                io.indices.iter().map(|i| *i as usize - 1)
+            })
+            .collect();
+        let n = self.inputs_to_modal_forces.len() / self.n_modes();
+        self.inputs_to_modal_forces
+            .chunks_mut(n)
+            .chain(
+                self.static_gain
+                    .iter_mut()
+                    .chain(self.named_static_gains.values_mut())
+                    .flat_map(|gain| gain.chunks_mut(self.n_io.0)),
+            )
+            .for_each(|row| indices.iter().for_each(|i| row[*i] *= factor));
+        Ok(self)
+    }
     /// Gets the number of inputs
     pub fn n_inputs(&self) -> usize {
         self.inputs
@@ -386,6 +999,149 @@ impl FEM {
             .filter_map(|x| x.as_ref())
             .fold(0usize, |a, x| a + x.len())
     }
+    /// Gets the number of turned-on nodes of a given output
+    ///
+    /// Consecutive entries sharing the same node id, or the same location if there is no node id,
+    /// belong to the same node
+    pub fn n_nodes(&self, id: usize) -> Option<usize> {
+        self.outputs.get(id).and_then(|x| x.as_ref()).map(|output| {
+            let mut nodes: Vec<_> = output
+                .iter()
+                .filter_map(|x| match x {
+                    IO::On(io) => Some((
+                        io.properties.node_id.as_ref(),
+                        io.properties.location.as_ref(),
+                    )),
+                    IO::Off(_) => None,
+                })
+                .collect();
+            nodes.dedup_by(|a, b| match (a.0, b.0) {
+                (Some(a), Some(b)) => a == b,
+                _ => a.1.is_some() && a.1 == b.1,
+            });
+            nodes.len()
+        })
+    }
+    /// Gets the `(min,max)` `[x,y,z]` coordinates of the turned-on nodes location of a given output
+    pub fn location_bounds(&self, id: usize) -> Option<([f64; 3], [f64; 3])> {
+        self.outputs
+            .get(id)
+            .and_then(|x| x.as_ref())?
+            .iter()
+            .filter_map(|x| match x {
+                IO::On(io) => io.properties.location.as_ref(),
+                IO::Off(_) => None,
+            })
+            .filter(|location| location.len() >= 3)
+            .fold(None, |bounds, location| {
+                let (mut min, mut max) =
+                    bounds.unwrap_or(([f64::INFINITY; 3], [f64::NEG_INFINITY; 3]));
+                min.iter_mut()
+                    .zip(max.iter_mut())
+                    .zip(location)
+                    .for_each(|((min, max), x)| {
+                        *min = min.min(*x);
+                        *max = max.max(*x);
+                    });
+                Some((min, max))
+            })
+    }
+    /// Gets the `[x,y]` coordinates of the turned-on nodes location of a given output
+    pub fn node_locations_2d(&self, id: usize) -> Option<Vec<[f64; 2]>> {
+        self.outputs.get(id).and_then(|x| x.as_ref()).map(|output| {
+            output
+                .iter()
+                .filter_map(|x| match x {
+                    IO::On(io) => io.properties.location.as_ref(),
+                    IO::Off(_) => None,
+                })
+                .filter(|location| location.len() >= 2)
+                .map(|location| [location[0], location[1]])
+                .collect()
+        })
+    }
+    /// Returns the Delaunay triangulation of the turned-on nodes `[x,y]` location of a given output
+    ///
+    /// Each triangle is given by the indices of its 3 vertices in [FEM::node_locations_2d]
+    #[cfg(feature = "triangle-rs")]
+    pub fn surface_triangulation(&self, id: usize) -> Option<Vec<[usize; 3]>> {
+        let nodes: Vec<f64> = self.node_locations_2d(id)?.into_iter().flatten().collect();
+        let delaunay = triangle_rs::Builder::new()
+            .set_tri_points(nodes)
+            .set_switches("Q")
+            .build();
+        Some(
+            delaunay
+                .triangle_iter()
+                .map(|t| [t[0], t[1], t[2]])
+                .collect(),
+        )
+    }
+    /// Gets the node ids of the turned-on entries of a given input
+    ///
+    /// Returns `None` if the input is not in the model or if one of the entries has no node id
+    pub fn input_node_ids(&self, id: usize) -> Option<Vec<u32>> {
+        node_ids(self.inputs.get(id).and_then(|x| x.as_ref())?.iter())
+    }
+    /// Gets the node ids of the turned-on entries of a given output
+    ///
+    /// Returns `None` if the output is not in the model or if one of the entries has no node id
+    pub fn output_node_ids(&self, id: usize) -> Option<Vec<u32>> {
+        node_ids(self.outputs.get(id).and_then(|x| x.as_ref())?.iter())
+    }
+    /// Checks if the turned-on nodes of a given input and of a given output are collocated
+    ///
+    /// The locations of the nodes are merged if they are within `tol` (Euclidean distance) of each other once sorted,
+    /// and the input and the output are collocated if each input location is within `tol` of an output location and vice versa.
+    /// Returns `None` if the input or the output is not selected or if one of them has no location
+    pub fn are_collocated(&self, input: usize, output: usize, tol: f64) -> Option<bool> {
+        fn is_near(a: &[f64], b: &[f64], tol: f64) -> bool {
+            a.len() == b.len()
+                && a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f64>() <= tol * tol
+        }
+        fn locations<'a>(io: impl Iterator<Item = &'a IO>, tol: f64) -> Option<Vec<&'a [f64]>> {
+            let mut locations: Vec<_> = io
+                .filter_map(|x| match x {
+                    IO::On(io) => io.properties.location.as_deref(),
+                    IO::Off(_) => None,
+                })
+                .collect();
+            locations.sort_by(|a, b| {
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(a, b)| a.total_cmp(b))
+                    .find(|x| x.is_ne())
+                    .unwrap_or_else(|| a.len().cmp(&b.len()))
+            });
+            locations.dedup_by(|a, b| is_near(a, b, tol));
+            (!locations.is_empty()).then_some(locations)
+        }
+        let i = locations(self.inputs.get(input)?.as_ref()?.iter(), tol)?;
+        let o = locations(self.outputs.get(output)?.as_ref()?.iter(), tol)?;
+        let covers =
+            |a: &[&[f64]], b: &[&[f64]]| a.iter().all(|a| b.iter().any(|b| is_near(a, b, tol)));
+        Some(covers(&i, &o) && covers(&o, &i))
+    }
+    /// Gets the range of a given input in the vector of turned-on inputs
+    fn input_range(&self, id: usize) -> Option<Range<usize>> {
+        self.inputs.get(id)?.as_ref().map(|input| {
+            let start = self.inputs[..id]
+                .iter()
+                .filter_map(|x| x.as_ref())
+                .fold(0usize, |a, x| a + x.len());
+            start..start + input.len()
+        })
+    }
+    /// Gets the range of a given output in the vector of turned-on outputs
+    fn output_range(&self, id: usize) -> Option<Range<usize>> {
+        self.outputs.get(id)?.as_ref().map(|output| {
+            let start = self.outputs[..id]
+                .iter()
+                .filter_map(|x| x.as_ref())
+                .fold(0usize, |a, x| a + x.len());
+            start..start + output.len()
+        })
+    }
 
     /// Loads FEM static solution gain matrix
     ///
@@ -407,8 +1163,39 @@ impl FEM {
             ..self
         })
     }
+    /// Loads FEM static solution gain matrix from a zip archive file
+    ///
+    /// The gain is read from the `static_gain` matrix of the archive, as [FEM::from_zip_archive] does for the dynamic model,
+    /// and replaces any static gain already loaded
+    pub fn with_static_gain_from_zip<P: AsRef<Path>>(self, path: P) -> Result<Self> {
+        let path = path.as_ref();
+        log::info!("Loading static gain from {path:?}");
+        let file = File::open(path)?;
+        let buffer = BufReader::new(file);
+        let mut zip_file = zip::ZipArchive::new(buffer)?;
+        let static_gain =
+            read_optional_mat(&mut zip_file, "static_gain")?.ok_or(FemError::StaticGain)?;
+        if static_gain.len() != self.n_io.0 * self.n_io.1 {
+            return Err(FemError::MalformedMatrix(
+                "static_gain".to_string(),
+                static_gain.len(),
+                self.n_io.1,
+                self.n_io.0,
+            ));
+        }
+        Ok(Self {
+            static_gain: Some(static_gain),
+            ..self
+        })
+    }
 
     /// Selects the inputs according to their natural ordering
+    ///
+    /// The inputs that are not selected are removed and are not restored by subsequent calls,
+    /// so successive calls intersect the selections: `keep_inputs(&[1]).keep_inputs(&[1, 2])` keeps only input #1.
+    /// The turned-off entries of the selected inputs are left as they are, see [FEM::retain_inputs] to also turn off entries.
+    /// The order of `id` does not matter, the inputs are always ordered by ascending group index,
+    /// e.g. `keep_inputs(&[3, 1])` orders the inputs of group #1 before the inputs of group #3
     pub fn keep_inputs(&mut self, id: &[usize]) -> &mut Self {
         self.inputs.iter_mut().enumerate().for_each(|(k, i)| {
             if !id.contains(&k) {
@@ -418,6 +1205,10 @@ impl FEM {
         self
     }
     /// Selects the inputs according to their natural ordering and some properties matching
+    ///
+    /// The entries of the selected inputs are reset: the matching entries are turned on,
+    /// even the ones turned off by a previous selection, and the others are turned off.
+    /// Use [FEM::retain_inputs_by] to intersect with a previous selection
     pub fn keep_inputs_by<F>(&mut self, id: &[usize], pred: F) -> &mut Self
     where
         F: Fn(&IOData) -> bool + Copy,
@@ -436,7 +1227,46 @@ impl FEM {
         });
         self
     }
+    /// Turns off the entries of the inputs that are not selected
+    ///
+    /// The inputs are not removed and no entry is turned on, so the selection is intersected with the entries already turned on
+    pub fn retain_inputs(&mut self, id: &[usize]) -> &mut Self {
+        self.retain_inputs_by(id, |_| true)
+    }
+    /// Turns off the entries of the inputs that are not selected and the entries of the selected inputs that do not match some properties
+    ///
+    /// The inputs are not removed and no entry is turned on, so the selection is intersected with the entries already turned on
+    pub fn retain_inputs_by<F>(&mut self, id: &[usize], pred: F) -> &mut Self
+    where
+        F: Fn(&IOData) -> bool + Copy,
+    {
+        self.inputs
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(k, i)| i.as_mut().map(|i| (k, i)))
+            .for_each(|(k, i)| {
+                i.iter_mut().for_each(|io| {
+                    if let IO::On(data) = io {
+                        if !(id.contains(&k) && pred(data)) {
+                            *io = io.clone().switch_off();
+                        }
+                    }
+                })
+            });
+        self
+    }
+    /// Selects the inputs according to their natural ordering and the matching of their descriptions with a regular expression
+    pub fn keep_inputs_by_regex(&mut self, id: &[usize], re: &str) -> Result<&mut Self> {
+        let re = regex::Regex::new(re)?;
+        Ok(self.keep_inputs_by(id, |x| re.is_match(&x.descriptions)))
+    }
     /// Selects the outputs according to their natural ordering
+    ///
+    /// The outputs that are not selected are removed and are not restored by subsequent calls,
+    /// so successive calls intersect the selections: `keep_outputs(&[1]).keep_outputs(&[1, 2])` keeps only output #1.
+    /// The turned-off entries of the selected outputs are left as they are, see [FEM::retain_outputs] to also turn off entries.
+    /// The order of `id` does not matter, the outputs are always ordered by ascending group index,
+    /// e.g. `keep_outputs(&[3, 1])` orders the outputs of group #1 before the outputs of group #3
     pub fn keep_outputs(&mut self, id: &[usize]) -> &mut Self {
         self.outputs.iter_mut().enumerate().for_each(|(k, i)| {
             if !id.contains(&k) {
@@ -446,6 +1276,10 @@ impl FEM {
         self
     }
     /// Selects the outputs according to their natural ordering and some properties matching
+    ///
+    /// The entries of the selected outputs are reset: the matching entries are turned on,
+    /// even the ones turned off by a previous selection, and the others are turned off.
+    /// Use [FEM::retain_outputs_by] to intersect with a previous selection
     pub fn keep_outputs_by<F>(&mut self, id: &[usize], pred: F) -> &mut Self
     where
         F: Fn(&IOData) -> bool + Copy,
@@ -464,6 +1298,213 @@ impl FEM {
         });
         self
     }
+    /// Turns off the entries of the outputs that are not selected
+    ///
+    /// The outputs are not removed and no entry is turned on, so the selection is intersected with the entries already turned on
+    pub fn retain_outputs(&mut self, id: &[usize]) -> &mut Self {
+        self.retain_outputs_by(id, |_| true)
+    }
+    /// Turns off the entries of the outputs that are not selected and the entries of the selected outputs that do not match some properties
+    ///
+    /// The outputs are not removed and no entry is turned on, so the selection is intersected with the entries already turned on
+    pub fn retain_outputs_by<F>(&mut self, id: &[usize], pred: F) -> &mut Self
+    where
+        F: Fn(&IOData) -> bool + Copy,
+    {
+        self.outputs
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(k, i)| i.as_mut().map(|i| (k, i)))
+            .for_each(|(k, i)| {
+                i.iter_mut().for_each(|io| {
+                    if let IO::On(data) = io {
+                        if !(id.contains(&k) && pred(data)) {
+                            *io = io.clone().switch_off();
+                        }
+                    }
+                })
+            });
+        self
+    }
+    /// Selects the outputs according to their natural ordering and the matching of their descriptions with a regular expression
+    pub fn keep_outputs_by_regex(&mut self, id: &[usize], re: &str) -> Result<&mut Self> {
+        let re = regex::Regex::new(re)?;
+        Ok(self.keep_outputs_by(id, |x| re.is_match(&x.descriptions)))
+    }
+    /// Renames the input `from` into `to`
+    ///
+    /// The entries of the group are moved unchanged to the `to` group,
+    /// which must be another FEM input name that is not already in the model
+    pub fn rename_input(&mut self, from: &str, to: &str) -> Result<&mut Self> {
+        let from_group = mem::discriminant(&fem_io::Inputs::try_from(from)?);
+        let to_group = mem::discriminant(&fem_io::Inputs::try_from(to)?);
+        if self
+            .inputs
+            .iter()
+            .flatten()
+            .any(|i| mem::discriminant(i) == to_group)
+        {
+            return Err(FemError::DuplicateIO(to.to_string()));
+        }
+        let group = self
+            .inputs
+            .iter_mut()
+            .flatten()
+            .find(|i| mem::discriminant(*i) == from_group)
+            .ok_or_else(|| FemError::MissingIO(from.to_string()))?;
+        *group = fem_io::Inputs::try_from((to.to_string(), group.iter().cloned().collect()))?;
+        Ok(self)
+    }
+    /// Renames the output `from` into `to`
+    ///
+    /// The entries of the group are moved unchanged to the `to` group,
+    /// which must be another FEM output name that is not already in the model
+    pub fn rename_output(&mut self, from: &str, to: &str) -> Result<&mut Self> {
+        let from_group = mem::discriminant(&fem_io::Outputs::try_from(from)?);
+        let to_group = mem::discriminant(&fem_io::Outputs::try_from(to)?);
+        if self
+            .outputs
+            .iter()
+            .flatten()
+            .any(|o| mem::discriminant(o) == to_group)
+        {
+            return Err(FemError::DuplicateIO(to.to_string()));
+        }
+        let group = self
+            .outputs
+            .iter_mut()
+            .flatten()
+            .find(|o| mem::discriminant(*o) == from_group)
+            .ok_or_else(|| FemError::MissingIO(from.to_string()))?;
+        *group = fem_io::Outputs::try_from((to.to_string(), group.iter().cloned().collect()))?;
+        Ok(self)
+    }
+    /// Merges the outputs `names` into the output `into`
+    ///
+    /// The entries of the outputs are concatenated in the order of `names` and the merged output
+    /// takes the place of the first of them, the other outputs are removed;
+    /// `into` must be a FEM output name that is either in `names` or not already in the model
+    pub fn merge_outputs(&mut self, names: &[&str], into: &str) -> Result<&mut Self> {
+        let groups = names
+            .iter()
+            .map(|name| Ok(mem::discriminant(&fem_io::Outputs::try_from(*name)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let into_group = mem::discriminant(&fem_io::Outputs::try_from(into)?);
+        if !groups.contains(&into_group)
+            && self
+                .outputs
+                .iter()
+                .flatten()
+                .any(|o| mem::discriminant(o) == into_group)
+        {
+            return Err(FemError::DuplicateIO(into.to_string()));
+        }
+        let ids = names
+            .iter()
+            .zip(&groups)
+            .map(|(name, group)| {
+                self.outputs
+                    .iter()
+                    .position(|o| o.as_ref().map(mem::discriminant).as_ref() == Some(group))
+                    .ok_or_else(|| FemError::MissingIO(name.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let Some(&first) = ids.first() else {
+            return Ok(self);
+        };
+        let io: Vec<_> = ids
+            .iter()
+            .filter_map(|&id| self.outputs[id].take())
+            .flat_map(|o| o.iter().cloned().collect::<Vec<_>>())
+            .collect();
+        self.outputs[first] = Some(fem_io::Outputs::try_from((into.to_string(), io))?);
+        Ok(self)
+    }
+    /// Returns a new FEM reduced to the given inputs and outputs
+    ///
+    /// The turned-off inputs and outputs of the selected groups are removed from the new FEM,
+    /// leaving `self` untouched
+    pub fn subset(&self, inputs: &[usize], outputs: &[usize]) -> FEM {
+        let mut fem = self.clone();
+        fem.keep_inputs(inputs).keep_outputs(outputs);
+        fem.inputs
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .for_each(|i| {
+                let io: Vec<_> = i.iter().filter(|io| io.is_on()).cloned().collect();
+                i.set(io);
+            });
+        fem.outputs
+            .iter_mut()
+            .filter_map(|x| x.as_mut())
+            .for_each(|i| {
+                let io: Vec<_> = i.iter().filter(|io| io.is_on()).cloned().collect();
+                i.set(io);
+            });
+        fem
+    }
+    /// Copies the inputs and outputs selection of another FEM
+    ///
+    /// The groups that are not selected in `other` are removed and,
+    /// within the other groups, each input or output takes the state (on or off) of the entry
+    /// of `other` with the same node id (or the same index if there is no node id) and the same component;
+    /// entries sharing a node id and a component, like the degrees of freedom of a node, are matched in order.
+    /// Entries without a match are turned off
+    pub fn clone_selection_from(&mut self, other: &FEM) -> &mut Self {
+        clone_groups_selection(
+            &mut self.inputs,
+            &other.inputs,
+            |x| switches(x.iter()),
+            |x, s| copy_switches(x.iter_mut(), s),
+        );
+        clone_groups_selection(
+            &mut self.outputs,
+            &other.outputs,
+            |x| switches(x.iter()),
+            |x, s| copy_switches(x.iter_mut(), s),
+        );
+        self
+    }
+    /// Turns off the inputs of a given input that do not correspond to the degrees of freedom `axes`
+    ///
+    /// `axes` are 0-based indices, e.g. `[2]` for the force along the z axis of a 6 degrees of freedom input.
+    /// The degrees of freedom are given by the `components` or `component` properties,
+    /// an error is returned if one of the turned-on inputs has neither,
+    /// as for the models loaded from a zip archive, or if the input is not selected
+    pub fn keep_input_components(&mut self, id: usize, axes: &[usize]) -> Result<&mut Self> {
+        let input = self
+            .inputs
+            .get_mut(id)
+            .and_then(|x| x.as_mut())
+            .ok_or_else(|| FemError::MissingIO(format!("input #{id}")))?;
+        let mask = components_mask(input.iter(), axes)?;
+        input.iter_mut().zip(mask).for_each(|(io, is_kept)| {
+            if !is_kept {
+                *io = io.clone().switch_off();
+            }
+        });
+        Ok(self)
+    }
+    /// Turns off the outputs of a given output that do not correspond to the degrees of freedom `axes`
+    ///
+    /// `axes` are 0-based indices, e.g. `[2]` for the displacement along the z axis of a 6 degrees of freedom output.
+    /// The degrees of freedom are given by the `components` or `component` properties,
+    /// an error is returned if one of the turned-on outputs has neither,
+    /// as for the models loaded from a zip archive, or if the output is not selected
+    pub fn keep_output_components(&mut self, id: usize, axes: &[usize]) -> Result<&mut Self> {
+        let output = self
+            .outputs
+            .get_mut(id)
+            .and_then(|x| x.as_mut())
+            .ok_or_else(|| FemError::MissingIO(format!("output #{id}")))?;
+        let mask = components_mask(output.iter(), axes)?;
+        output.iter_mut().zip(mask).for_each(|(io, is_kept)| {
+            if !is_kept {
+                *io = io.clone().switch_off();
+            }
+        });
+        Ok(self)
+    }
     /// Filters the inputs according to some properties matching
     pub fn filter_inputs_by<F>(&mut self, id: &[usize], pred: F) -> &mut Self
     where
@@ -523,6 +1564,8 @@ impl FEM {
         self
     }
     /// Returns the inputs 2 modes transformation matrix for the turned-on inputs
+    ///
+    /// The inputs are ordered by ascending group index
     pub fn inputs2modes(&mut self) -> Vec<f64> {
         let indices: Vec<u32> = self
             .inputs
@@ -547,9 +1590,17 @@ impl FEM {
             })
             .collect()
     }
+    /// Returns the inputs 2 modes transformation matrix `[n_modes,n_inputs]` for the turned-on inputs
+    pub fn force_to_mode_matrix(&mut self) -> na::DMatrix<f64> {
+        let n_modes = self.n_modes();
+        let inputs2modes = self.inputs2modes();
+        na::DMatrix::from_row_slice(n_modes, inputs2modes.len() / n_modes, &inputs2modes)
+    }
     /// Returns the inputs 2 modes transformation matrix for a given input
+    ///
+    /// Returns `None` if the input is not selected or if `id` is out of range
     pub fn input2modes(&self, id: usize) -> Option<Vec<f64>> {
-        self.inputs[id].as_ref().map(|input| {
+        self.inputs.get(id).and_then(|x| x.as_ref()).map(|input| {
             let indices: Vec<u32> = input
                 .iter()
                 .filter_map(|x| match x {
@@ -570,13 +1621,30 @@ impl FEM {
                 .collect()
         })
     }
+    /// Returns the inputs 2 modes `[n_modes,n_inputs]` and the modes 2 outputs `[n_outputs,n_modes]` matrices for a given input and a given output
+    pub fn modal_io(
+        &self,
+        input: usize,
+        output: usize,
+    ) -> Option<(na::DMatrix<f64>, na::DMatrix<f64>)> {
+        let n_modes = self.n_modes();
+        let forces_2_modes = self.input2modes(input)?;
+        let modes_2_nodes = self.modes2output(output)?;
+        Some((
+            na::DMatrix::from_row_slice(n_modes, forces_2_modes.len() / n_modes, &forces_2_modes),
+            na::DMatrix::from_row_slice(modes_2_nodes.len() / n_modes, n_modes, &modes_2_nodes),
+        ))
+    }
+    /// Returns the columns of a `[n,n_inputs]` matrix corresponding to the turned-on inputs of a given input
+    ///
+    /// Returns `None` if the input is not selected or if `id` is out of range
     pub fn trim2input(&self, id: usize, matrix: &na::DMatrix<f64>) -> Option<na::DMatrix<f64>> {
         /*assert_eq!(
             matrix.ncols(),
             self.n_inputs(),
             "Matrix columns # do not match inputs #"
         );*/
-        self.inputs[id].as_ref().map(|input| {
+        self.inputs.get(id).and_then(|x| x.as_ref()).map(|input| {
             let indices: Vec<u32> = input
                 .iter()
                 .filter_map(|x| match x {
@@ -594,6 +1662,8 @@ impl FEM {
         })
     }
     /// Returns the modes 2 outputs transformation matrix for the turned-on outputs
+    ///
+    /// The outputs are ordered by ascending group index
     pub fn modes2outputs(&mut self) -> Vec<f64> {
         let n = self.n_modes();
         let q: Vec<_> = self.modal_disp_to_outputs.chunks(n).collect();
@@ -611,10 +1681,18 @@ impl FEM {
             .cloned()
             .collect()
     }
+    /// Returns the modes 2 outputs transformation matrix `[n_outputs,n_modes]` for the turned-on outputs
+    pub fn mode_to_output_matrix(&mut self) -> na::DMatrix<f64> {
+        let n_modes = self.n_modes();
+        let modes2outputs = self.modes2outputs();
+        na::DMatrix::from_row_slice(modes2outputs.len() / n_modes, n_modes, &modes2outputs)
+    }
     /// Returns the modes 2 outputs transformation matrix for a given output
+    ///
+    /// Returns `None` if the output is not selected or if `id` is out of range
     pub fn modes2output(&self, id: usize) -> Option<Vec<f64>> {
         let q: Vec<_> = self.modal_disp_to_outputs.chunks(self.n_modes()).collect();
-        self.outputs[id].as_ref().map(|output| {
+        self.outputs.get(id).and_then(|x| x.as_ref()).map(|output| {
             output
                 .iter()
                 .filter_map(|x| match x {
@@ -627,14 +1705,112 @@ impl FEM {
                 .collect()
         })
     }
-    pub fn trim2output(&self, id: usize, matrix: &na::DMatrix<f64>) -> Option<na::DMatrix<f64>> {
-        /*         assert_eq!(
-            matrix.nrows(),
-            self.n_outputs(),
-            "Matrix rows # do not match outputs #"
-        ); */
-        //let q: Vec<_> = matrix.chunks(self.n_modes()).collect();
-        self.outputs[id].as_ref().map(|output| {
+    /// Returns the modes sorted by decreasing contribution to a given output
+    ///
+    /// Each mode is given with the norm of its column in the modes 2 outputs transformation matrix of the output
+    pub fn output_mode_sensitivity(&self, id: usize) -> Option<Vec<(usize, f64)>> {
+        let n_modes = self.n_modes();
+        let modes_2_nodes = self.modes2output(id)?;
+        let mut norms: Vec<_> = (0..n_modes)
+            .map(|k| {
+                (
+                    k,
+                    modes_2_nodes
+                        .iter()
+                        .skip(k)
+                        .step_by(n_modes)
+                        .map(|x| x * x)
+                        .sum::<f64>()
+                        .sqrt(),
+                )
+            })
+            .collect();
+        norms.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Some(norms)
+    }
+    /// Returns the fraction of the DC gain energy of a given output that is retained by a subset of the modes
+    ///
+    /// The DC gain from the turned-on inputs to the output is `sum_k c_k b_k^T / w_k^2`, where `b_k` and `c_k` are the rows and columns of the
+    /// inputs 2 modes and modes 2 outputs matrices and `w_k` is the eigen frequency in radians.
+    /// The fraction is the ratio of the squared Frobenius norms of the DC gain summed over `modes` (0-based indices) and over all the modes.
+    /// The rigid body modes, with a zero frequency, are skipped.
+    /// Returns `None` if the output is not selected, if one of the modes is out of range or if the DC gain of the output is zero
+    pub fn retained_energy_fraction(&mut self, output: usize, modes: &[usize]) -> Option<f64> {
+        let n_modes = self.n_modes();
+        if modes.iter().any(|&k| k >= n_modes) {
+            return None;
+        }
+        let mut modes = modes.to_vec();
+        modes.sort_unstable();
+        modes.dedup();
+        let modes_2_nodes = na::DMatrix::from_row_slice(
+            self.outputs.get(output)?.as_ref()?.len(),
+            n_modes,
+            &self.modes2output(output)?,
+        );
+        let forces_2_modes =
+            na::DMatrix::from_row_slice(n_modes, self.n_inputs(), &self.inputs2modes());
+        let w = self.eigen_frequencies_to_radians();
+        let energy = |modes: &[usize]| {
+            modes
+                .iter()
+                .copied()
+                .filter(|&k| w[k] > 0f64)
+                .fold(
+                    na::DMatrix::<f64>::zeros(modes_2_nodes.nrows(), forces_2_modes.ncols()),
+                    |gain, k| {
+                        gain + modes_2_nodes.column(k) * forces_2_modes.row(k) / (w[k] * w[k])
+                    },
+                )
+                .norm_squared()
+        };
+        let full_energy = energy(&(0..n_modes).collect::<Vec<_>>());
+        (full_energy > 0f64).then(|| energy(&modes) / full_energy)
+    }
+    /// Returns the output Gramian diagonal of a given output
+    ///
+    /// The contribution of mode #k is `||c_k||²/(2ζ_kω_k)`, where `c_k` is the column #k
+    /// of the modes 2 outputs transformation matrix of the output, `ζ_k` the damping coefficient
+    /// and `ω_k` the eigen frequency `[rd/s]` of the mode;
+    /// the modes with `ζ_kω_k=0` (e.g. the rigid body modes) have an infinite contribution
+    pub fn output_gramian_diagonal(&self, id: usize) -> Option<Vec<f64>> {
+        let n_modes = self.n_modes();
+        let modes_2_nodes = self.modes2output(id)?;
+        Some(
+            self.eigen_frequencies_to_radians()
+                .into_iter()
+                .zip(&self.proportional_damping_vec)
+                .enumerate()
+                .map(|(k, (w, z))| {
+                    let c2 = modes_2_nodes
+                        .iter()
+                        .skip(k)
+                        .step_by(n_modes)
+                        .map(|x| x * x)
+                        .sum::<f64>();
+                    let zw = 2. * z * w;
+                    if zw > 0f64 {
+                        c2 / zw
+                    } else {
+                        f64::INFINITY
+                    }
+                })
+                .collect(),
+        )
+    }
+    /// Returns the rows of a `[n_outputs,n]` matrix corresponding to the turned-on outputs of a given output
+    ///
+    /// An error is returned if the number of rows of the matrix does not match the number of outputs of the FEM
+    /// and `None` if the output is not selected or if `id` is out of range
+    pub fn trim2output(
+        &self,
+        id: usize,
+        matrix: &na::DMatrix<f64>,
+    ) -> Result<Option<na::DMatrix<f64>>> {
+        if matrix.nrows() != self.n_io.1 {
+            return Err(FemError::OutputsMismatch(matrix.nrows(), self.n_io.1));
+        }
+        Ok(self.outputs.get(id).and_then(|x| x.as_ref()).map(|output| {
             na::DMatrix::from_rows(
                 &output
                     .iter()
@@ -646,56 +1822,107 @@ impl FEM {
                     .map(|i| matrix.row(i as usize - 1))
                     .collect::<Vec<_>>(),
             )
-        })
+        }))
+    }
+    /// Returns the Hankel singular values of the modes restricted to the turned-on entries of a given input and a given output
+    ///
+    /// For mode k, the value is `0.25 |b_k| |c_k| / (w_k z_k)` where `b_k` is the mode row of [FEM::input2modes],
+    /// `c_k` the mode column of [FEM::modes2output], `w_k` the eigen frequency in radians and `z_k` the damping coefficient.
+    /// Modes with a zero frequency or a zero damping (rigid body modes) are given the value `f64::INFINITY` so they are always kept.
+    /// Returns `None` if either the input or the output is not selected
+    pub fn channel_hankel_singular_values(&self, input: usize, output: usize) -> Option<Vec<f64>> {
+        let b = self.input2modes(input)?;
+        let c = self.modes2output(output)?;
+        let n_modes = self.n_modes();
+        let n_in = b.len() / n_modes;
+        Some(
+            self.eigen_frequencies_to_radians()
+                .into_iter()
+                .zip(&self.proportional_damping_vec)
+                .enumerate()
+                .map(|(k, (w, z))| {
+                    let wz = w * z;
+                    if wz == 0f64 {
+                        return f64::INFINITY;
+                    }
+                    let b_norm = b[k * n_in..(k + 1) * n_in]
+                        .iter()
+                        .map(|x| x * x)
+                        .sum::<f64>()
+                        .sqrt();
+                    let c_norm = c
+                        .iter()
+                        .skip(k)
+                        .step_by(n_modes)
+                        .map(|x| x * x)
+                        .sum::<f64>()
+                        .sqrt();
+                    0.25 * b_norm * c_norm / wz
+                })
+                .collect(),
+        )
     }
 
     /// Return the static gain reduced to the turned-on inputs and outputs
+    ///
+    /// The gain is the `static_gain` loaded with the model, either from the zip archive ([FEM::from_zip_archive], [FEM::with_static_gain_from_zip])
+    /// or from a pickle file ([FEM::static_from_env])
     pub fn reduced_static_gain(&mut self) -> Option<na::DMatrix<f64>> {
         log::info!("computing static gain");
+        self.static_gain.as_ref().map(|gain| self.reduce_gain(gain))
+    }
+    /// Return a named static gain reduced to the turned-on inputs and outputs
+    ///
+    /// The named static gains are the `static_gain_<name>` matrices of the zip archive
+    pub fn named_static_gain(&self, name: &str) -> Option<na::DMatrix<f64>> {
+        log::info!("computing {name} static gain");
+        self.named_static_gains
+            .get(name)
+            .map(|gain| self.reduce_gain(gain))
+    }
+    /// Reduces a `[n_outputs,n_inputs]` (row wise) gain matrix to the turned-on inputs and outputs
+    fn reduce_gain(&self, gain: &[f64]) -> na::DMatrix<f64> {
         let n_io = self.n_io;
         let n_reduced_io = (self.n_inputs(), self.n_outputs());
-        self.static_gain
-            .as_ref()
-            .map(|gain| {
-                let indices: Vec<u32> = self
-                    .inputs
-                    .iter()
-                    .filter_map(|x| x.as_ref())
-                    .flat_map(|v| {
-                        v.iter().filter_map(|x| match x {
-                            IO::On(io) => Some(io.indices.clone()),
-                            IO::Off(_) => None,
-                        })
-                    })
-                    .flatten()
-                    .collect();
-                let n = n_io.0;
-                let reduced_inputs_gain: Vec<f64> = gain
-                    .chunks(n)
-                    .flat_map(|x| {
-                        indices
-                            .iter()
-                            .map(|i| x[*i as usize - 1])
-                            .collect::<Vec<f64>>()
-                    })
-                    .collect();
-                let n = n_reduced_io.0;
-                let q: Vec<_> = reduced_inputs_gain.chunks(n).collect();
-                self.outputs
+        let indices: Vec<u32> = self
+            .inputs
+            .iter()
+            .filter_map(|x| x.as_ref())
+            .flat_map(|v| {
+                v.iter().filter_map(|x| match x {
+                    IO::On(io) => Some(io.indices.clone()),
+                    IO::Off(_) => None,
+                })
+            })
+            .flatten()
+            .collect();
+        let n = n_io.0;
+        let reduced_inputs_gain: Vec<f64> = gain
+            .chunks(n)
+            .flat_map(|x| {
+                indices
                     .iter()
-                    .filter_map(|x| x.as_ref())
-                    .flat_map(|v| {
-                        v.iter().filter_map(|x| match x {
-                            IO::On(io) => Some(io.indices.clone()),
-                            IO::Off(_) => None,
-                        })
-                    })
-                    .flatten()
-                    .flat_map(|i| q[i as usize - 1])
-                    .cloned()
+                    .map(|i| x[*i as usize - 1])
                     .collect::<Vec<f64>>()
             })
-            .map(|new_gain| na::DMatrix::from_row_slice(n_reduced_io.1, n_reduced_io.0, &new_gain))
+            .collect();
+        let n = n_reduced_io.0;
+        let q: Vec<_> = reduced_inputs_gain.chunks(n).collect();
+        let new_gain: Vec<f64> = self
+            .outputs
+            .iter()
+            .filter_map(|x| x.as_ref())
+            .flat_map(|v| {
+                v.iter().filter_map(|x| match x {
+                    IO::On(io) => Some(io.indices.clone()),
+                    IO::Off(_) => None,
+                })
+            })
+            .flatten()
+            .flat_map(|i| q[i as usize - 1])
+            .cloned()
+            .collect();
+        na::DMatrix::from_row_slice(n_reduced_io.1, n_reduced_io.0, &new_gain)
     }
     /// Returns the FEM static gain for the turned-on inputs and outputs
     pub fn static_gain(&mut self) -> na::DMatrix<f64> {
@@ -713,23 +1940,130 @@ impl FEM {
         // println!("{ }",d.fixed_slice::<3,3>(0,0)); <- Just checking if unstable modes were removed
         modes_2_nodes.remove_columns(0, 3) * d * forces_2_modes.remove_rows(0, 3)
     }
+    /// Returns the static gain for the turned-on inputs and outputs, preferring the stored static gain
+    ///
+    /// The stored static gain ([FEM::reduced_static_gain]) is returned if present,
+    /// otherwise the gain is computed from the modes ([FEM::static_gain]).
+    /// The 2 may differ slightly as the modal computation is limited to the modes of the model
+    /// and skips the first 3 (rigid body) modes whereas the stored gain is the full static solution
+    pub fn static_gain_preferring_stored(&mut self) -> na::DMatrix<f64> {
+        match self.reduced_static_gain() {
+            Some(gain) => gain,
+            None => self.static_gain(),
+        }
+    }
+    /// Returns the condition number of the static gain reduced to the turned-on inputs and outputs
+    ///
+    /// The condition number is the ratio of the largest to the smallest singular value of the gain
+    pub fn static_gain_condition_number(&mut self) -> Option<f64> {
+        self.reduced_static_gain().map(|gain| {
+            let s = gain.svd(false, false).singular_values;
+            s.max() / s.min()
+        })
+    }
+    /// Returns the static deformation of a given output under a constant acceleration applied to a given input
+    ///
+    /// The input must be a 3 degrees of freedom force input with its entries ordered as `[x,y,z]` for each node,
+    /// the force `masses[k] * g_vector` is applied to node #k.
+    /// `None` is returned if the input does not have exactly 3 turned-on entries per node
+    /// (nodes are identified by their location) or if there is not one mass per node
+    pub fn gravity_sag(
+        &mut self,
+        input: usize,
+        output: usize,
+        masses: &[f64],
+        g_vector: [f64; 3],
+    ) -> Option<Vec<f64>> {
+        let in_range = self.input_range(input)?;
+        let mut nodes: Vec<_> = self.inputs[input]
+            .as_ref()?
+            .iter()
+            .filter_map(|x| match x {
+                IO::On(io) => Some(io.properties.location.as_ref()),
+                IO::Off(_) => None,
+            })
+            .collect();
+        nodes.dedup();
+        if nodes.len() != masses.len() || 3 * nodes.len() != in_range.len() {
+            return None;
+        }
+        let out_range = self.output_range(output)?;
+        let gain = self.reduced_static_gain()?;
+        let mut force = na::DVector::<f64>::zeros(gain.ncols());
+        force
+            .rows_mut(in_range.start, in_range.len())
+            .iter_mut()
+            .zip(masses.iter().flat_map(|m| g_vector.map(|g| m * g)))
+            .for_each(|(f, mg)| *f = mg);
+        let sag = gain * force;
+        Some(sag.as_slice()[out_range].to_vec())
+    }
+    /// Writes the selected inputs and outputs properties to a JSON file
+    ///
+    /// Each group is written with its index, its name, its number of turned-on entries and,
+    /// for each of its turned-on entries, the index, the description, the node id, the coordinate system label and the location
+    #[cfg(feature = "serde_json")]
+    pub fn dump_io_json<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        fn io_json<'a>(io: impl Iterator<Item = &'a IO>) -> Vec<serde_json::Value> {
+            io.filter_map(|io| match io {
+                IO::On(data) => Some(serde_json::json!({
+                    "indices": data.indices,
+                    "description": data.descriptions,
+                    "nodeID": data.properties.node_id,
+                    "csLabel": data.properties.cs_label,
+                    "location": data.properties.location,
+                })),
+                IO::Off(_) => None,
+            })
+            .collect()
+        }
+        let inputs: Vec<_> = self
+            .inputs_iter()
+            .map(|(k, name, n)| {
+                let io = self.inputs[k].iter().flat_map(|x| x.iter());
+                serde_json::json!({"group": k, "name": name, "count": n, "io": io_json(io)})
+            })
+            .collect();
+        let outputs: Vec<_> = self
+            .outputs_iter()
+            .map(|(k, name, n)| {
+                let io = self.outputs[k].iter().flat_map(|x| x.iter());
+                serde_json::json!({"group": k, "name": name, "count": n, "io": io_json(io)})
+            })
+            .collect();
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(
+            file,
+            &serde_json::json!({"inputs": inputs, "outputs": outputs}),
+        )?;
+        Ok(())
+    }
+    /// Writes the static gain reduced to the turned-on inputs and outputs to a NumPy `.npy` file
+    pub fn write_static_gain_npy<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let gain = self.reduced_static_gain().ok_or(FemError::StaticGain)?;
+        npy::write(path, gain.shape(), gain.as_slice(), true)
+    }
+    /// Writes the inputs 2 modes transformation matrix `[n_modes,n_inputs]` for the turned-on inputs to a NumPy `.npy` file
+    pub fn write_inputs2modes_npy<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let shape = (self.n_modes(), self.n_inputs());
+        npy::write(path, shape, &self.inputs2modes(), false)
+    }
+    /// Writes the modes 2 outputs transformation matrix `[n_outputs,n_modes]` for the turned-on outputs to a NumPy `.npy` file
+    pub fn write_modes2outputs_npy<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let shape = (self.n_outputs(), self.n_modes());
+        npy::write(path, shape, &self.modes2outputs(), false)
+    }
 }
 impl fmt::Display for FEM {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let ins = self
-            .inputs
-            .iter()
-            .enumerate()
-            .filter_map(|(k, x)| x.as_ref().and_then(|x| Some((k, x))))
-            .map(|(k, x)| format!(" #{:02} {}", k, x))
+            .inputs_iter()
+            .map(|(k, name, n)| format!(" #{:02} {:>24}: [{:5}]", k, name, n))
             .collect::<Vec<String>>()
             .join("\n");
         let outs = self
-            .outputs
-            .iter()
-            .enumerate()
-            .filter_map(|(k, x)| x.as_ref().and_then(|x| Some((k, x))))
-            .map(|(k, x)| format!(" #{:02} {}", k, x))
+            .outputs_iter()
+            .map(|(k, name, n)| format!(" #{:02} {:>24}: [{:5}]", k, name, n))
             .collect::<Vec<String>>()
             .join("\n");
         let min_damping = self
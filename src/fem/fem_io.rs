@@ -1,6 +1,7 @@
 //! # FEM inputs/outputs definitions
 
 use super::io::IO;
+use crate::FemError;
 
 type Item = (String, Vec<IO>);
 
@@ -16,3 +17,102 @@ mod outputs {
 }
 pub use inputs::Inputs;
 pub use outputs::Outputs;
+
+include!(concat!(env!("OUT_DIR"), "/fem_io_names.rs"));
+
+/// Edit (Levenshtein) distance between 2 strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+/// Returns up to 3 names the closest to `name`
+fn closest_names(name: &str, names: &[&str]) -> Vec<String> {
+    let mut distances: Vec<_> = names
+        .iter()
+        .map(|x| (edit_distance(name, x), x.to_string()))
+        .collect();
+    distances.sort();
+    distances.into_iter().take(3).map(|(_, x)| x).collect()
+}
+
+/// Converts an input name into an empty group of inputs
+///
+/// On failure, the error gives the closest valid input names
+impl TryFrom<&str> for Inputs {
+    type Error = FemError;
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Inputs::try_from((name.to_string(), vec![])).map_err(|_| {
+            FemError::UnknownIO(name.to_string(), closest_names(name, FEM_INPUT_NAMES))
+        })
+    }
+}
+/// Converts an output name into an empty group of outputs
+///
+/// On failure, the error gives the closest valid output names
+impl TryFrom<&str> for Outputs {
+    type Error = FemError;
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Outputs::try_from((name.to_string(), vec![])).map_err(|_| {
+            FemError::UnknownIO(name.to_string(), closest_names(name, FEM_OUTPUT_NAMES))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("OSSM1Lcl", "OSSM1Lcl"), 0);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("flaw", "lawn"), 2);
+        assert_eq!(edit_distance("OSSM1Lcl", "OSSM1Lcl6F"), 2);
+    }
+    #[test]
+    fn closest() {
+        let names = [
+            "MC_M2_lcl_6D",
+            "OSS_M1_lcl",
+            "OSS_M1_lcl_6F",
+            "M1_actuators_segment_1",
+        ];
+        assert_eq!(
+            closest_names("OSS_M1_Lcl", &names),
+            vec!["OSS_M1_lcl", "OSS_M1_lcl_6F", "MC_M2_lcl_6D"]
+        );
+        assert_eq!(
+            closest_names("OSS_M1_lcl", &names[..1]),
+            vec!["MC_M2_lcl_6D"]
+        );
+        assert!(closest_names("OSS_M1_lcl", &[]).is_empty());
+    }
+    #[test]
+    fn unknown_io_message() {
+        assert_eq!(
+            FemError::UnknownIO("OSS_M1_Lcl".to_string(), vec![]).to_string(),
+            "unknown FEM input or output OSS_M1_Lcl"
+        );
+        assert_eq!(
+            FemError::UnknownIO(
+                "OSS_M1_Lcl".to_string(),
+                vec!["OSS_M1_lcl".to_string(), "OSS_M1_lcl_6F".to_string()]
+            )
+            .to_string(),
+            "unknown FEM input or output OSS_M1_Lcl, closest matches: OSS_M1_lcl, OSS_M1_lcl_6F"
+        );
+    }
+}
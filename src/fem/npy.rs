@@ -0,0 +1,73 @@
+//! # NumPy `.npy` writer
+//!
+//! Minimal writer of 2D `f64` arrays in the [NPY format version 1.0](https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html)
+
+use crate::Result;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+/// Writes a `[rows,cols]` matrix to a `.npy` file
+///
+/// `data` is stored row wise if `fortran_order` is `false` and column wise otherwise
+pub fn write<P: AsRef<Path>>(
+    path: P,
+    shape: (usize, usize),
+    data: &[f64],
+    fortran_order: bool,
+) -> Result<()> {
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': {}, 'shape': ({}, {}), }}",
+        if fortran_order { "True" } else { "False" },
+        shape.0,
+        shape.1
+    );
+    // magic string (6) + version (2) + header length (2) + header + '\n' is a multiple of 64
+    let padding = 63 - (10 + header.len()) % 64;
+    header.push_str(&" ".repeat(padding));
+    header.push('\n');
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(b"\x93NUMPY\x01\x00")?;
+    file.write_all(&(header.len() as u16).to_le_bytes())?;
+    file.write_all(header.as_bytes())?;
+    for x in data {
+        file.write_all(&x.to_le_bytes())?;
+    }
+    file.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_and_data() -> Result<()> {
+        let data = [1f64, 2., 3., 4., 5., 6.];
+        for fortran_order in [false, true] {
+            let file = tempfile::NamedTempFile::new()?;
+            write(file.path(), (2, 3), &data, fortran_order)?;
+            let bytes = std::fs::read(file.path())?;
+            assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+            let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+            assert_eq!((10 + header_len) % 64, 0);
+            let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+            assert!(header.ends_with('\n'));
+            assert_eq!(
+                header.trim_end(),
+                format!(
+                    "{{'descr': '<f8', 'fortran_order': {}, 'shape': (2, 3), }}",
+                    if fortran_order { "True" } else { "False" }
+                )
+            );
+            let values: Vec<f64> = bytes[10 + header_len..]
+                .chunks(8)
+                .map(|x| f64::from_le_bytes(x.try_into().unwrap()))
+                .collect();
+            assert_eq!(values, data);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! # Frequency units
+//!
+//! Newtypes for frequencies in Hz and in radians per second
+
+use std::{f64::consts::PI, fmt};
+
+/// Frequency in Hz
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct Hz(pub f64);
+
+/// Angular frequency in radians per second
+#[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd)]
+pub struct RadPerSec(pub f64);
+
+impl From<RadPerSec> for Hz {
+    fn from(value: RadPerSec) -> Self {
+        Hz(value.0 / (2. * PI))
+    }
+}
+impl From<Hz> for RadPerSec {
+    fn from(value: Hz) -> Self {
+        RadPerSec(2. * PI * value.0)
+    }
+}
+impl From<Hz> for f64 {
+    fn from(value: Hz) -> Self {
+        value.0
+    }
+}
+impl From<RadPerSec> for f64 {
+    fn from(value: RadPerSec) -> Self {
+        value.0
+    }
+}
+impl fmt::Display for Hz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}Hz", self.0)
+    }
+}
+impl fmt::Display for RadPerSec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}rd/s", self.0)
+    }
+}
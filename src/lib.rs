@@ -2,5 +2,6 @@ mod fem;
 pub use fem::{
     fem_io,
     io::{IOData, Properties, IO},
-    FemError, Result, FEM,
+    units::{Hz, RadPerSec},
+    FemError, FemLoadOpts, FemMetadata, Result, FEM,
 };
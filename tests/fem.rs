@@ -1,4 +1,27 @@
-use gmt_fem::{fem_io, Switch, FEM};
+use gmt_fem::{fem_io, Switch, FEM, IO};
+use nalgebra as na;
+
+/// Inputs names of the test FEM
+const INPUTS: [&str; 3] = [
+    "MC_M2_S1_VC_delta_F",
+    "MC_M2_S2_VC_delta_F",
+    "MC_M2_S3_VC_delta_F",
+];
+/// Outputs names of the test FEM
+const OUTPUTS: [&str; 4] = [
+    "MC_M2_S1_VC_delta_D",
+    "MC_M2_S2_VC_delta_D",
+    "MC_M2_S3_VC_delta_D",
+    "MC_M2_S4_VC_delta_D",
+];
+/// Returns a synthetic FEM with 10 modes, the first 3 being rigid body modes
+///
+/// The FEM has the first inputs and outputs of [INPUTS] and [OUTPUTS] with the given number of entries
+fn test_fem(inputs: &[usize], outputs: &[usize]) -> anyhow::Result<FEM> {
+    let inputs: Vec<_> = INPUTS.into_iter().zip(inputs.iter().copied()).collect();
+    let outputs: Vec<_> = OUTPUTS.into_iter().zip(outputs.iter().copied()).collect();
+    Ok(FEM::synthetic(10, &inputs, &outputs)?)
+}
 
 #[test]
 fn keep_switch() -> anyhow::Result<()> {
@@ -64,3 +87,606 @@ fn switch_by_name() -> anyhow::Result<()> {
     println!("Size: [{},{}]", fem.n_inputs(), fem.n_outputs());
     Ok(())
 }
+#[test]
+fn transpose() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[6], &[4])?;
+    let (inputs_to_modal_forces, modal_disp_to_outputs) = (
+        fem.inputs_to_modal_forces.clone(),
+        fem.modal_disp_to_outputs.clone(),
+    );
+    fem.transpose_modal_matrices();
+    assert_eq!(
+        na::DMatrix::from_row_slice(10, 6, &fem.inputs_to_modal_forces),
+        na::DMatrix::from_column_slice(10, 6, &inputs_to_modal_forces)
+    );
+    assert_eq!(
+        na::DMatrix::from_row_slice(4, 10, &fem.modal_disp_to_outputs),
+        na::DMatrix::from_column_slice(4, 10, &modal_disp_to_outputs)
+    );
+    fem.transpose_modal_matrices();
+    assert_eq!(fem.inputs_to_modal_forces, inputs_to_modal_forces);
+    assert_eq!(fem.modal_disp_to_outputs, modal_disp_to_outputs);
+    Ok(())
+}
+
+#[test]
+fn rayleigh_damping() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2], &[2])?;
+    let (alpha, beta) = (0.1, 1e-3);
+    fem.rayleigh_damping(alpha, beta);
+    assert_eq!(
+        fem.proportional_damping_vec.len(),
+        fem.eigen_frequencies.len()
+    );
+    for (&f, &zeta) in fem
+        .eigen_frequencies
+        .iter()
+        .zip(&fem.proportional_damping_vec)
+    {
+        let w = 2. * std::f64::consts::PI * f;
+        if w > 0. {
+            assert!((zeta - 0.5 * (alpha / w + beta * w)).abs() < 1e-12);
+        } else {
+            assert_eq!(zeta, 0.);
+        }
+    }
+    Ok(())
+}
+#[test]
+fn subset() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3, 3], &[2, 2])?;
+    fem.inputs[1]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .take(1)
+        .for_each(|io| *io = io.clone().switch_off());
+    let in1 = fem.input2modes(1).unwrap();
+    let mut reduced = fem.subset(&[1], &[0, 1]);
+    assert!(reduced.inputs[0].is_none());
+    assert!(reduced.inputs[1]
+        .as_ref()
+        .unwrap()
+        .iter()
+        .all(|io| io.is_on()));
+    assert_eq!((reduced.n_inputs(), reduced.n_outputs()), (2, 4));
+    assert_eq!(reduced.input2modes(1).unwrap(), in1);
+    assert_eq!((fem.n_inputs(), fem.n_outputs()), (5, 4));
+    assert_eq!(reduced.reduced_static_gain().unwrap().shape(), (4, 2));
+    Ok(())
+}
+#[test]
+fn io_iter() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2, 3], &[1, 2, 3])?;
+    fem.keep_outputs(&[0, 2]);
+    assert_eq!(
+        fem.inputs_iter()
+            .map(|(k, _, n)| (k, n))
+            .collect::<Vec<_>>(),
+        vec![(0, 2), (1, 3)]
+    );
+    assert_eq!(
+        fem.outputs_iter()
+            .map(|(k, _, n)| (k, n))
+            .collect::<Vec<_>>(),
+        vec![(0, 1), (2, 3)]
+    );
+    for (k, name, _) in fem.outputs_iter() {
+        assert!(!name.is_empty());
+        assert!(fem.outputs[k].as_ref().unwrap().to_string().contains(&name));
+    }
+    Ok(())
+}
+#[test]
+fn channel_hankel_singular_values() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2, 3], &[2, 4])?;
+    fem.keep_outputs(&[1]);
+    assert!(fem.channel_hankel_singular_values(1, 0).is_none());
+    let hsv = fem.channel_hankel_singular_values(1, 1).unwrap();
+    assert_eq!(hsv.len(), fem.n_modes());
+    let b = na::DMatrix::from_row_slice(fem.n_modes(), 3, &fem.input2modes(1).unwrap());
+    let c = na::DMatrix::from_row_slice(4, fem.n_modes(), &fem.modes2output(1).unwrap());
+    let w = fem.eigen_frequencies_to_radians();
+    for (k, &value) in hsv.iter().enumerate() {
+        if w[k] == 0. {
+            assert_eq!(value, f64::INFINITY);
+        } else {
+            let expected = 0.25 * b.row(k).norm() * c.column(k).norm()
+                / (w[k] * fem.proportional_damping_vec[k]);
+            assert!((value - expected).abs() < 1e-9 * expected);
+        }
+    }
+    assert_eq!(hsv.iter().filter(|x| x.is_infinite()).count(), 3);
+    Ok(())
+}
+#[test]
+fn scale_units() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[6], &[4])?;
+    let mut scaled = fem.clone().scale_units(1e-3, 1e3).clone();
+    fem.inputs_to_modal_forces
+        .iter()
+        .zip(&scaled.inputs_to_modal_forces)
+        .for_each(|(x, y)| assert!((x * 1e-3 - y).abs() < 1e-15));
+    fem.modal_disp_to_outputs
+        .iter()
+        .zip(&scaled.modal_disp_to_outputs)
+        .for_each(|(x, y)| assert!((x * 1e-3 - y).abs() < 1e-15));
+    assert_eq!(
+        scaled.outputs[0].as_ref().unwrap().iter().next().map(|io| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.location.clone().unwrap()
+        }),
+        fem.outputs[0].as_ref().unwrap().iter().next().map(|io| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties
+                .location
+                .iter()
+                .flatten()
+                .map(|x| x * 1e-3)
+                .collect()
+        })
+    );
+    // the static gain stays consistent with the scaled modal matrices
+    let g = scaled.reduced_static_gain().unwrap();
+    assert!((&g - scaled.static_gain()).abs().max() < 1e-9 * g.abs().max());
+    let g_archive = fem.reduced_static_gain().unwrap();
+    assert!((g - g_archive.clone() * 1e-6).abs().max() < 1e-12 * g_archive.abs().max() * 1e-6);
+    Ok(())
+}
+#[test]
+fn gravity_sag() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[6], &[4])?;
+    // the locations are random: each entry is a node
+    assert!(fem.gravity_sag(0, 0, &[1.; 6], [0., 0., -9.8]).is_none());
+    // 2 nodes with 3 entries each
+    fem.inputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.location = Some(vec![(k / 3) as f64, 0., 0.]);
+        });
+    assert!(fem.gravity_sag(0, 0, &[1.], [0., 0., -9.8]).is_none());
+    let sag = fem.gravity_sag(0, 0, &[1., 2.], [0., 0., -9.8]).unwrap();
+    let force = na::DVector::from_column_slice(&[0., 0., -9.8, 0., 0., -19.6]);
+    let expected = fem.reduced_static_gain().unwrap() * force;
+    assert_eq!(sag.len(), 4);
+    assert!(
+        (na::DVector::from_column_slice(&sag) - expected)
+            .abs()
+            .max()
+            < 1e-12
+    );
+    Ok(())
+}
+#[test]
+fn clone_selection_from() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[6], &[2, 2])?;
+    // 2 nodes with 3 degrees of freedom each
+    fem.inputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.node_id = Some(vec![k as u32 / 3]);
+        });
+    let mut other = fem.clone();
+    other.inputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .filter(|(k, _)| *k == 1 || *k == 5)
+        .for_each(|(_, io)| *io = io.clone().switch_off());
+    other.keep_outputs(&[1]);
+    // the same model with the nodes in reverse order
+    let input = fem.inputs[0].as_mut().unwrap();
+    let mut io: Vec<_> = input.iter().cloned().collect();
+    io.rotate_left(3);
+    input.set(io);
+    fem.clone_selection_from(&other);
+    assert_eq!(
+        fem.inputs[0]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|io| io.is_on())
+            .collect::<Vec<_>>(),
+        vec![true, true, false, true, false, true]
+    );
+    assert!(fem.outputs[0].is_none());
+    assert_eq!(fem.n_outputs(), 2);
+    Ok(())
+}
+#[test]
+fn n_nodes() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[1], &[6, 2])?;
+    // the locations are random: each entry is a node
+    assert_eq!(fem.n_nodes(0), Some(6));
+    // 2 nodes with 3 entries each
+    fem.outputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.node_id = Some(vec![k as u32 / 3]);
+        });
+    assert_eq!(fem.n_nodes(0), Some(2));
+    fem.keep_outputs(&[0]);
+    assert_eq!(fem.n_nodes(1), None);
+    assert_eq!(fem.n_nodes(2), None);
+    Ok(())
+}
+#[test]
+fn location_bounds() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[1], &[4, 2])?;
+    fem.outputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            let x = k as f64;
+            data.properties.location = Some(vec![x, -x, 2. * x]);
+        });
+    assert_eq!(fem.location_bounds(0), Some(([0., -3., 0.], [3., 0., 6.])));
+    // the turned-off nodes are ignored
+    fem.keep_outputs_by(&[0, 1], |io| io.indices[0] < 4);
+    assert_eq!(fem.location_bounds(0), Some(([0., -2., 0.], [2., 0., 4.])));
+    assert_eq!(fem.location_bounds(1), None);
+    fem.keep_outputs(&[0]);
+    assert_eq!(fem.location_bounds(1), None);
+    Ok(())
+}
+#[test]
+fn keep_components() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[6], &[4, 2])?;
+    // the models loaded from zip archives have no component data
+    assert!(fem.keep_input_components(0, &[2]).is_err());
+    // 2 nodes with 3 forces each
+    fem.inputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.component = Some(vec![(k % 3 + 1) as i32]);
+        });
+    // 4 nodes with the x and y or z displacements
+    fem.outputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.components = Some(if k % 2 == 0 {
+                vec![1., 1., 0.]
+            } else {
+                vec![0., 0., 1.]
+            });
+        });
+    fem.keep_input_components(0, &[2])?
+        .keep_output_components(0, &[0])?;
+    let is_on = |io: &IO| io.is_on();
+    assert_eq!(
+        fem.inputs[0]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(is_on)
+            .collect::<Vec<_>>(),
+        vec![false, false, true, false, false, true]
+    );
+    assert_eq!(
+        fem.outputs[0]
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(is_on)
+            .collect::<Vec<_>>(),
+        vec![true, false, true, false]
+    );
+    fem.keep_outputs(&[0]);
+    assert!(fem.keep_output_components(1, &[0]).is_err());
+    assert!(fem.keep_output_components(2, &[0]).is_err());
+    Ok(())
+}
+#[test]
+fn named_static_gain() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2, 3], &[2, 2])?;
+    let gain = fem.static_gain.clone().unwrap();
+    fem.named_static_gains
+        .insert("M2".to_string(), gain.iter().map(|x| 2. * x).collect());
+    fem.keep_inputs(&[1]).keep_outputs(&[0]);
+    assert!(fem.named_static_gain("M1").is_none());
+    let named_gain = fem.named_static_gain("M2").unwrap();
+    assert_eq!(named_gain.shape(), (2, 3));
+    assert_eq!(named_gain, fem.reduced_static_gain().unwrap() * 2.);
+    Ok(())
+}
+#[test]
+fn keep_intersect() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2, 2, 2], &[2])?;
+    fem.keep_inputs(&[1]).keep_inputs(&[1, 2]);
+    assert_eq!(
+        fem.inputs_iter()
+            .map(|(k, _, n)| (k, n))
+            .collect::<Vec<_>>(),
+        vec![(1, 2)]
+    );
+    Ok(())
+}
+#[test]
+fn retain() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2, 3, 2], &[2, 2])?;
+    // keep_inputs_by resets the selection of the selected inputs
+    fem.keep_inputs_by(&[0, 1, 2], |io| io.indices[0] % 2 == 0)
+        .keep_inputs_by(&[0, 1, 2], |io| io.indices[0] > 2);
+    assert_eq!(fem.n_inputs(), 5);
+    // retain_inputs_by intersects with the previous selection
+    fem.keep_inputs_by(&[0, 1, 2], |io| io.indices[0] % 2 == 0)
+        .retain_inputs_by(&[0, 1, 2], |io| io.indices[0] > 2);
+    assert_eq!(
+        fem.inputs_iter()
+            .map(|(k, _, n)| (k, n))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (1, 1), (2, 1)]
+    );
+    fem.retain_inputs(&[1]).retain_outputs(&[0]);
+    assert_eq!(
+        fem.inputs_iter()
+            .map(|(k, _, n)| (k, n))
+            .collect::<Vec<_>>(),
+        vec![(0, 0), (1, 1), (2, 0)]
+    );
+    assert_eq!(
+        fem.outputs_iter()
+            .map(|(k, _, n)| (k, n))
+            .collect::<Vec<_>>(),
+        vec![(0, 2), (1, 0)]
+    );
+    Ok(())
+}
+#[test]
+fn apply_gain() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3], &[2])?;
+    let gain = fem.static_gain.clone().unwrap();
+    fem.named_static_gains.insert("M2".to_string(), gain);
+    let mut scaled = fem.clone().apply_gain(0.5).clone();
+    assert_eq!(
+        scaled.modes2output(0).unwrap(),
+        fem.modes2output(0)
+            .unwrap()
+            .into_iter()
+            .map(|x| x * 0.5)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        scaled.reduced_static_gain().unwrap(),
+        fem.reduced_static_gain().unwrap() * 0.5
+    );
+    assert_eq!(
+        scaled.named_static_gain("M2").unwrap(),
+        fem.named_static_gain("M2").unwrap() * 0.5
+    );
+    Ok(())
+}
+#[test]
+fn output_mode_sensitivity() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3], &[2, 4])?;
+    fem.keep_outputs(&[1]);
+    assert!(fem.output_mode_sensitivity(0).is_none());
+    let sensitivity = fem.output_mode_sensitivity(1).unwrap();
+    assert_eq!(sensitivity.len(), fem.n_modes());
+    let c = na::DMatrix::from_row_slice(4, fem.n_modes(), &fem.modes2output(1).unwrap());
+    for (k, norm) in &sensitivity {
+        assert!((c.column(*k).norm() - norm).abs() < 1e-12);
+    }
+    assert!(sensitivity.windows(2).all(|x| x[0].1 >= x[1].1));
+    let mut modes: Vec<_> = sensitivity.iter().map(|(k, _)| *k).collect();
+    modes.sort();
+    assert_eq!(modes, (0..fem.n_modes()).collect::<Vec<_>>());
+    Ok(())
+}
+#[test]
+fn synthetic() -> anyhow::Result<()> {
+    let mut fem = FEM::synthetic(10, &[(INPUTS[0], 6)], &[(OUTPUTS[0], 4)])?;
+    println!("{fem}");
+    assert_eq!((fem.n_inputs(), fem.n_outputs()), (6, 4));
+    let g = fem.reduced_static_gain().unwrap();
+    assert_eq!(g.shape(), (4, 6));
+    assert!((g - fem.static_gain()).abs().max() < 1e-9);
+    assert!(FEM::synthetic(10, &[("MC_M2_S1_VC_delta", 6)], &[(OUTPUTS[0], 4)]).is_err());
+    Ok(())
+}
+#[test]
+fn retained_energy_fraction() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3, 2], &[2, 4])?;
+    fem.keep_outputs(&[1]);
+    assert!(fem.retained_energy_fraction(0, &[3]).is_none());
+    assert!(fem.retained_energy_fraction(1, &[10]).is_none());
+    let all_modes: Vec<_> = (0..fem.n_modes()).collect();
+    assert!((fem.retained_energy_fraction(1, &all_modes).unwrap() - 1.).abs() < 1e-12);
+    // the rigid body modes have no contribution
+    assert_eq!(fem.retained_energy_fraction(1, &[0, 1, 2]), Some(0.));
+    let g = fem.reduced_static_gain().unwrap();
+    assert!((fem.retained_energy_fraction(1, &all_modes[3..]).unwrap() - 1.).abs() < 1e-12);
+    // the DC gain of the last mode only
+    let w = 2. * std::f64::consts::PI * fem.eigen_frequencies[9];
+    let b = na::DMatrix::from_row_slice(fem.n_modes(), 5, &fem.inputs2modes());
+    let c = na::DMatrix::from_row_slice(4, fem.n_modes(), &fem.modes2output(1).unwrap());
+    let g9 = c.column(9) * b.row(9) / (w * w);
+    let fraction = fem.retained_energy_fraction(1, &[9, 9]).unwrap();
+    assert!((fraction - g9.norm_squared() / g.norm_squared()).abs() < 1e-9);
+    Ok(())
+}
+#[test]
+fn description() -> anyhow::Result<()> {
+    let fem = test_fem(&[1], &[1])?;
+    assert_eq!(fem.description(), "synthetic FEM");
+    Ok(())
+}
+#[test]
+fn rename() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3, 3], &[2, 2])?;
+    assert!(fem.rename_input(INPUTS[0], INPUTS[1]).is_err());
+    assert!(fem.rename_input(INPUTS[2], INPUTS[0]).is_err());
+    assert!(fem.rename_output(OUTPUTS[0], OUTPUTS[1]).is_err());
+    fem.rename_input(INPUTS[0], INPUTS[2])?
+        .rename_output(OUTPUTS[1], OUTPUTS[2])?;
+    let is_group = |group: &fem_io::Inputs, name: &str| {
+        fem_io::Inputs::try_from(name)
+            .is_ok_and(|x| std::mem::discriminant(&x) == std::mem::discriminant(group))
+    };
+    assert!(is_group(fem.inputs[0].as_ref().unwrap(), INPUTS[2]));
+    assert_eq!(fem.inputs[0].as_ref().unwrap().len(), 3);
+    assert!(fem_io::Outputs::try_from(OUTPUTS[2])
+        .is_ok_and(|x| std::mem::discriminant(&x)
+            == std::mem::discriminant(fem.outputs[1].as_ref().unwrap())));
+    assert_eq!((fem.n_inputs(), fem.n_outputs()), (6, 4));
+    Ok(())
+}
+#[test]
+fn node_ids() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3], &[2])?;
+    // the synthetic model has no node ids
+    assert!(fem.input_node_ids(0).is_none());
+    fem.inputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.node_id = Some(vec![10 + k as u32]);
+        });
+    fem.keep_inputs_by(&[0], |io| io.indices[0] != 2);
+    assert_eq!(fem.input_node_ids(0), Some(vec![10, 12]));
+    assert!(fem.output_node_ids(1).is_none());
+    Ok(())
+}
+#[test]
+fn are_collocated() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[6], &[4, 2])?;
+    // the inputs: 2 nodes with 3 forces each
+    fem.inputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            data.properties.location = Some(vec![(k / 3) as f64, 1., 0.]);
+        });
+    // the outputs: the same 2 nodes in reverse order and slightly apart, with 2 displacements each
+    fem.outputs[0]
+        .as_mut()
+        .unwrap()
+        .iter_mut()
+        .enumerate()
+        .for_each(|(k, io)| {
+            let (IO::On(data) | IO::Off(data)) = io;
+            let x = if k < 2 { 1. } else { 0. } + 1e-6 * k as f64;
+            data.properties.location = Some(vec![x, 1., 0.]);
+        });
+    assert_eq!(fem.are_collocated(0, 0, 1e-3), Some(true));
+    assert_eq!(fem.are_collocated(0, 0, 1e-9), Some(false));
+    // output #1 has random locations
+    assert_eq!(fem.are_collocated(0, 1, 1e-3), Some(false));
+    // only one of the output nodes is turned on
+    fem.keep_outputs_by(&[0, 1], |io| io.indices[0] < 3);
+    assert_eq!(fem.are_collocated(0, 0, 1e-3), Some(false));
+    // none of the outputs #1 is turned on
+    assert_eq!(fem.are_collocated(0, 1, 1e-3), None);
+    fem.keep_outputs(&[0]);
+    assert_eq!(fem.are_collocated(0, 1, 1e-3), None);
+    Ok(())
+}
+#[test]
+fn scale_input() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3, 2], &[4])?;
+    let (in0, in1) = (fem.input2modes(0).unwrap(), fem.input2modes(1).unwrap());
+    let g = fem.reduced_static_gain().unwrap();
+    fem.scale_input(1, 2.)?;
+    assert_eq!(fem.input2modes(0).unwrap(), in0);
+    assert_eq!(
+        fem.input2modes(1).unwrap(),
+        in1.iter().map(|x| 2. * x).collect::<Vec<_>>()
+    );
+    let scaled_g = fem.reduced_static_gain().unwrap();
+    assert_eq!(scaled_g.columns(0, 3), g.columns(0, 3));
+    assert_eq!(scaled_g.columns(3, 2), g.columns(3, 2) * 2.);
+    fem.keep_inputs(&[0]);
+    assert!(fem.scale_input(1, 2.).is_err());
+    assert!(fem.scale_input(2, 2.).is_err());
+    Ok(())
+}
+#[test]
+fn merge_outputs() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3], &[2, 3, 4])?;
+    let (out0, out2) = (fem.modes2output(0).unwrap(), fem.modes2output(2).unwrap());
+    assert!(fem
+        .merge_outputs(&[OUTPUTS[2], OUTPUTS[0]], OUTPUTS[1])
+        .is_err());
+    assert!(fem
+        .merge_outputs(&[OUTPUTS[3], OUTPUTS[0]], OUTPUTS[3])
+        .is_err());
+    fem.merge_outputs(&[OUTPUTS[2], OUTPUTS[0]], OUTPUTS[2])?;
+    assert!(fem.outputs[0].is_none());
+    assert_eq!(fem.outputs[2].as_ref().unwrap().len(), 6);
+    assert!(fem_io::Outputs::try_from(OUTPUTS[2])
+        .is_ok_and(|x| std::mem::discriminant(&x)
+            == std::mem::discriminant(fem.outputs[2].as_ref().unwrap())));
+    assert_eq!(fem.modes2output(2).unwrap(), [out2, out0].concat());
+    assert_eq!(fem.n_outputs(), 9);
+    Ok(())
+}
+#[test]
+fn output_gramian_diagonal() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[3], &[2, 4])?;
+    fem.keep_outputs(&[1]);
+    assert!(fem.output_gramian_diagonal(0).is_none());
+    let diagonal = fem.output_gramian_diagonal(1).unwrap();
+    assert_eq!(diagonal.len(), fem.n_modes());
+    let c = na::DMatrix::from_row_slice(4, fem.n_modes(), &fem.modes2output(1).unwrap());
+    let w = fem.eigen_frequencies_to_radians();
+    for (k, &value) in diagonal.iter().enumerate() {
+        if w[k] == 0. {
+            assert_eq!(value, f64::INFINITY);
+        } else {
+            let expected =
+                c.column(k).norm_squared() / (2. * fem.proportional_damping_vec[k] * w[k]);
+            assert!((value - expected).abs() < 1e-9 * expected);
+        }
+    }
+    Ok(())
+}
+#[test]
+fn keep_order() -> anyhow::Result<()> {
+    let mut fem = test_fem(&[2], &[1, 2, 3, 4])?;
+    fem.keep_outputs(&[3, 1]);
+    assert_eq!(
+        fem.outputs
+            .iter()
+            .enumerate()
+            .filter_map(|(k, x)| x.as_ref().map(|_| k))
+            .collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    let y: Vec<f64> = [fem.modes2output(1), fem.modes2output(3)]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect();
+    assert_eq!(fem.modes2outputs(), y);
+    Ok(())
+}